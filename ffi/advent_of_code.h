@@ -0,0 +1,125 @@
+/* The C interface exported by src/ffi.rs, maintained by hand.
+ * Keep it in step with the exports there, its tests check every one is
+ * declared. */
+
+#ifndef ADVENT_OF_CODE_H
+#define ADVENT_OF_CODE_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * The part was solved.
+ */
+#define AOC_OK 0
+
+/*
+ * The day, part, options or input are invalid.
+ */
+#define AOC_ERROR 1
+
+/*
+ * The solver panicked.
+ */
+#define AOC_PANIC 2
+
+/*
+ * The outcome of aoc_run.
+ */
+typedef struct AocResult AocResult;
+
+/*
+ * Writes up to capacity days implemented for year to days, in order, and
+ * returns how many there are in total.
+ *
+ * Safety:
+ *
+ * days must point to capacity writable bytes, or be NULL if capacity is 0.
+ */
+size_t aoc_days(uint16_t year, uint8_t *days, size_t capacity);
+
+/*
+ * The title of a day, or NULL if it is not implemented. Free it with
+ * aoc_string_free.
+ */
+char *aoc_day_title(uint16_t year, uint8_t day);
+
+/*
+ * Frees a string returned by this library. Does nothing for NULL.
+ *
+ * Safety:
+ *
+ * s must be NULL or a string returned by this library that was not freed
+ * yet.
+ */
+void aoc_string_free(char *s);
+
+/*
+ * Solves part of a day of year on the input_len bytes at input.
+ * options holds the day options as name=value separated by whitespace, or
+ * is NULL for none; strict rejects malformed input lines. Never returns
+ * NULL; free the result with aoc_result_free.
+ *
+ * Safety:
+ *
+ * input must point to input_len readable bytes, or be NULL if input_len
+ * is 0. options must be NULL or a NUL-terminated string.
+ */
+AocResult *aoc_run(uint16_t year, uint8_t day, uint8_t part, const uint8_t *input, size_t input_len, const char *options, bool strict);
+
+/*
+ * AOC_OK, AOC_ERROR or AOC_PANIC.
+ *
+ * Safety:
+ *
+ * result must be a result of aoc_run that was not freed yet.
+ */
+int32_t aoc_result_status(const AocResult *result);
+
+/*
+ * The answer, or NULL if the part failed. Valid until the result is freed.
+ *
+ * Safety:
+ *
+ * result must be a result of aoc_run that was not freed yet.
+ */
+const char *aoc_result_answer(const AocResult *result);
+
+/*
+ * Why the part failed, or NULL if it was solved. Valid until the result is
+ * freed.
+ *
+ * Safety:
+ *
+ * result must be a result of aoc_run that was not freed yet.
+ */
+const char *aoc_result_error(const AocResult *result);
+
+/*
+ * How long parsing and solving took, in nanoseconds.
+ *
+ * Safety:
+ *
+ * result must be a result of aoc_run that was not freed yet.
+ */
+uint64_t aoc_result_time_ns(const AocResult *result);
+
+/*
+ * Frees a result of aoc_run. Does nothing for NULL.
+ *
+ * Safety:
+ *
+ * result must be NULL or a result of aoc_run that was not freed yet.
+ */
+void aoc_result_free(AocResult *result);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ADVENT_OF_CODE_H */
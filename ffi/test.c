@@ -0,0 +1,81 @@
+/* Exercises the C interface of the solvers. Build and run it from the root of
+ * the repository with
+ *
+ *     cargo build
+ *     cc -Iffi ffi/test.c target/debug/libadvent_of_code_rs.a -lpthread -ldl -lm \
+ *         -o target/ffi-test
+ *     target/ffi-test
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "advent_of_code.h"
+
+static int failures = 0;
+
+static void expect(int condition, const char *what) {
+    if (!condition) {
+        fprintf(stderr, "FAILED: %s\n", what);
+        failures++;
+    }
+}
+
+/* Solves a part and compares the answer, or the start of the error message. */
+static void expect_run(uint8_t day, uint8_t part, const char *input, const char *options,
+                       int32_t status, const char *expected) {
+    AocResult *result = aoc_run(2022, day, part, (const uint8_t *)input, strlen(input),
+                                options, false);
+    const char *answer = aoc_result_answer(result);
+    const char *error = aoc_result_error(result);
+
+    printf("day %d part %d: status %d, answer %s, error %s, %llu ns\n", day, part,
+           aoc_result_status(result), answer ? answer : "(null)", error ? error : "(null)",
+           (unsigned long long)aoc_result_time_ns(result));
+    expect(aoc_result_status(result) == status, "status");
+    if (status == AOC_OK) {
+        expect(answer != NULL && strcmp(answer, expected) == 0, "answer");
+        expect(error == NULL, "no error");
+    } else {
+        expect(answer == NULL, "no answer");
+        expect(error != NULL && strncmp(error, expected, strlen(expected)) == 0, "error");
+    }
+    aoc_result_free(result);
+}
+
+int main(void) {
+    uint8_t days[32];
+    size_t count = aoc_days(2022, days, sizeof days);
+    expect(count >= 11 && count <= sizeof days, "day count");
+    for (size_t i = 0; i < count; i++) {
+        char *title = aoc_day_title(2022, days[i]);
+        printf("day %d: %s\n", days[i], title);
+        expect(title != NULL, "title");
+        aoc_string_free(title);
+    }
+    expect(aoc_days(1999, NULL, 0) == 0, "no days of 1999");
+    expect(aoc_day_title(2022, 42) == NULL, "no title of day 42");
+
+    const char *pairs = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n";
+    const char *motions = "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2\n";
+    expect_run(4, 1, pairs, NULL, AOC_OK, "2");
+    expect_run(4, 2, pairs, "", AOC_OK, "4");
+    expect_run(9, 2, motions, "knots=2", AOC_OK, "13");
+    expect_run(9, 2, motions, "knots", AOC_ERROR, "Invalid option knots");
+    expect_run(4, 3, pairs, NULL, AOC_ERROR, "Unknown part 3");
+    expect_run(42, 1, pairs, NULL, AOC_ERROR, "Day 42 of 2022 not implemented");
+
+    const char *malformed = "2-4,6-8\nnonsense\n";
+    AocResult *strict =
+        aoc_run(2022, 4, 1, (const uint8_t *)malformed, strlen(malformed), NULL, true);
+    expect(aoc_result_status(strict) == AOC_ERROR, "strict parsing");
+    aoc_result_free(strict);
+
+    if (failures > 0) {
+        fprintf(stderr, "%d checks failed\n", failures);
+        return EXIT_FAILURE;
+    }
+    printf("ok\n");
+    return EXIT_SUCCESS;
+}
@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use crate::solution::Part;
+
+/// File name of the answer store inside the input directory.
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+const HEADER: &str = "# day part \"input\" options answer";
+
+/// The known correct answers, one per day, part, input set and options.
+///
+/// They are stored one per line as `<day> <part> "<input>" <options> <answer>`.
+/// The options are the `name=value` pairs that differ from the defaults of the
+/// day, separated by commas, or `-` for none. Backslashes and line breaks in the
+/// input and the answer are escaped as `\\` and `\n`, quotes in the input as
+/// `\"`.
+#[derive(Debug, Default)]
+pub struct Answers {
+    entries: BTreeMap<(u8, Part, String, String), String>,
+}
+
+impl Answers {
+    /// Loads the answers from `path`, which may not exist yet.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Answers::default()),
+            Err(err) => return Err(err),
+        };
+
+        let mut answers = Answers::default();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (day, part, input, options, answer) = parse_line(line).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "{}:{}: expected `<day> <part> \"<input>\" <options> <answer>`, found `{}`",
+                        path.display(),
+                        index + 1,
+                        line
+                    ),
+                )
+            })?;
+            answers
+                .entries
+                .insert((day, part, input, options.to_owned()), answer);
+        }
+        Ok(answers)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let mut content = String::from(HEADER);
+        content.push('\n');
+        for ((day, part, input, options), answer) in &self.entries {
+            content.push_str(&format_line(*day, *part, input, options, answer));
+            content.push('\n');
+        }
+        fs::write(path, content)
+    }
+
+    /// The answer for the input and the `options` that differ from the
+    /// defaults, see [`crate::options::Options::changed`].
+    pub fn get(&self, day: u8, part: Part, input: &str, options: &[String]) -> Option<&str> {
+        self.entries
+            .get(&(day, part, input.to_owned(), options_field(options)))
+            .map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u8, part: Part, input: &str, options: &[String], answer: &str) {
+        self.entries.insert(
+            (day, part, input.to_owned(), options_field(options)),
+            answer.to_owned(),
+        );
+    }
+}
+
+fn options_field(options: &[String]) -> String {
+    match options {
+        [] => "-".to_owned(),
+        options => options.join(","),
+    }
+}
+
+fn format_line(day: u8, part: Part, input: &str, options: &str, answer: &str) -> String {
+    format!(
+        "{:02} {} \"{}\" {} {}",
+        day,
+        part,
+        escape(input).replace('"', "\\\""),
+        options,
+        escape(answer)
+    )
+}
+
+fn parse_line(line: &str) -> Option<(u8, Part, String, &str, String)> {
+    let (day, rest) = line.split_once(' ')?;
+    let (part, rest) = rest.split_once(' ')?;
+    let (input, rest) = unquote(rest)?;
+    let (options, answer) = rest.strip_prefix(' ')?.split_once(' ')?;
+    let day = day.parse().ok()?;
+    let part = Part::try_from(part).ok()?;
+    Some((day, part, input, options, unescape(answer)?))
+}
+
+/// Splits the quoted string at the start of `text` off the rest, undoing the
+/// escapes.
+fn unquote(text: &str) -> Option<(String, &str)> {
+    let text = text.strip_prefix('"')?;
+    let mut escaped = false;
+    let end = text.char_indices().find_map(|(index, c)| match c {
+        '"' if !escaped => Some(index),
+        _ => {
+            escaped = !escaped && c == '\\';
+            None
+        }
+    })?;
+    Some((unescape(&text[..end])?, &text[end + 1..]))
+}
+
+fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> Option<String> {
+    let mut res = String::with_capacity(answer.len());
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'n' => res.push('\n'),
+                '\\' => res.push('\\'),
+                '"' => res.push('"'),
+                _ => return None,
+            },
+            c => res.push(c),
+        }
+    }
+    Some(res)
+}
+
+/// Line by line comparison of an expected and an actual answer, marking
+/// expected lines with `-` and actual lines with `+`.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+
+    let mut res = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => res.push_str(&format!("  {}\n", e)),
+            (e, a) => {
+                if let Some(e) = e {
+                    res.push_str(&format!("- {}\n", e));
+                }
+                if let Some(a) = a {
+                    res.push_str(&format!("+ {}\n", a));
+                }
+            }
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_and_unescape_should_round_trip() {
+        for answer in ["12", "a\\b", "##..\n..##", "\\n"] {
+            assert_eq!(Some(answer.to_owned()), unescape(&escape(answer)));
+        }
+    }
+
+    #[test]
+    fn parse_line_should_keep_spaces_in_answer() {
+        let (day, part, input, options, answer) =
+            parse_line("10 2 \"example\" - #. .#\\n.#").unwrap();
+
+        assert_eq!(10, day);
+        assert_eq!(Part::Two, part);
+        assert_eq!("example", input);
+        assert_eq!("-", options);
+        assert_eq!("#. .#\n.#", answer);
+    }
+
+    #[test]
+    fn format_line_and_parse_line_should_round_trip() {
+        for input in ["default", "my inputs/day 09.txt", "\"quoted\\\"", "\\"] {
+            let line = format_line(9, Part::Two, input, "knots=2", "13");
+
+            assert_eq!(
+                Some((9, Part::Two, input.to_owned(), "knots=2", "13".to_owned())),
+                parse_line(&line),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn answers_should_depend_on_options() {
+        let mut answers = Answers::default();
+        answers.insert(9, Part::Two, "default", &[], "2000");
+        answers.insert(9, Part::Two, "default", &["knots=2".into()], "6000");
+
+        assert_eq!(Some("2000"), answers.get(9, Part::Two, "default", &[]));
+        assert_eq!(
+            Some("6000"),
+            answers.get(9, Part::Two, "default", &["knots=2".into()])
+        );
+        assert_eq!(None, answers.get(9, Part::Two, "example", &[]));
+    }
+
+    #[test]
+    fn diff_should_mark_changed_lines() {
+        assert_eq!("  a\n- b\n+ c\n+ d\n", diff("a\nb", "a\nc\nd"));
+    }
+}
@@ -0,0 +1,271 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::execute::Config;
+use crate::report::format_duration;
+use crate::solution::{Part, Runner};
+
+const HEADER: &str = "# day phase min_ns median_ns mean_ns stddev_ns";
+
+/// What is being timed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Solve(Part),
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Solve(part) => write!(f, "part{}", part),
+        }
+    }
+}
+
+impl TryFrom<&str> for Phase {
+    type Error = ();
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        match s {
+            "parse" => Ok(Phase::Parse),
+            _ => s
+                .strip_prefix("part")
+                .and_then(|part| Part::try_from(part).ok())
+                .map(Phase::Solve)
+                .ok_or(()),
+        }
+    }
+}
+
+/// Summary of the samples of one phase.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let n = sorted.len().max(1) as f64;
+        let nanos: Vec<f64> = sorted.iter().map(|d| d.as_nanos() as f64).collect();
+        let mean = nanos.iter().sum::<f64>() / n;
+        let variance = if nanos.len() > 1 {
+            nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+
+        let median = match sorted.len() {
+            0 => Duration::ZERO,
+            len if len % 2 == 1 => sorted[len / 2],
+            len => (sorted[len / 2 - 1] + sorted[len / 2]) / 2,
+        };
+
+        Stats {
+            min: sorted.first().copied().unwrap_or_default(),
+            median,
+            mean: Duration::from_nanos(mean as u64),
+            stddev: Duration::from_nanos(variance.sqrt() as u64),
+        }
+    }
+}
+
+/// The timings of one phase of a day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub day: u8,
+    pub phase: Phase,
+    pub stats: Stats,
+}
+
+/// Times parsing and the given parts of a day `iterations` times, after running
+/// each of them `warmup` times untimed. Reading the input is not timed.
+pub fn bench_day(
+    runner: &dyn Runner,
+    parts: &[Part],
+    config: &Config,
+    warmup: usize,
+    iterations: usize,
+) -> Result<Vec<Measurement>> {
+    let input = config.read_input(runner.day())?;
+    let measurement = |phase, samples: &[Duration]| Measurement {
+        day: runner.day(),
+        phase,
+        stats: Stats::from_samples(samples),
+    };
+
+    for _ in 0..warmup {
+        runner.parse(&input, config.strictness)?;
+    }
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        runner.parse(&input, config.strictness)?;
+        samples.push(start.elapsed());
+    }
+    let mut measurements = vec![measurement(Phase::Parse, &samples)];
+
+    let parsed = runner.parse(&input, config.strictness)?;
+    for &part in parts {
+        let options = config.options(runner, part)?;
+        for _ in 0..warmup {
+            parsed.solve(part, &options);
+        }
+        samples.clear();
+        for _ in 0..iterations {
+            let start = Instant::now();
+            parsed.solve(part, &options);
+            samples.push(start.elapsed());
+        }
+        measurements.push(measurement(Phase::Solve(part), &samples));
+    }
+
+    Ok(measurements)
+}
+
+/// Saves measurements so that a later run can be compared against them.
+pub fn save(path: &Path, measurements: &[Measurement]) -> Result<()> {
+    let mut content = String::from(HEADER);
+    content.push('\n');
+    for Measurement { day, phase, stats } in measurements {
+        content.push_str(&format!(
+            "{:02} {} {} {} {} {}\n",
+            day,
+            phase,
+            stats.min.as_nanos(),
+            stats.median.as_nanos(),
+            stats.mean.as_nanos(),
+            stats.stddev.as_nanos()
+        ));
+    }
+    Ok(fs::write(path, content)?)
+}
+
+pub fn load(path: &Path) -> Result<Vec<Measurement>> {
+    let content = fs::read_to_string(path)?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| {
+            parse_measurement(line).ok_or_else(|| {
+                Error::Failed(format!(
+                    "{}:{}: expected `{}`, found `{}`",
+                    path.display(),
+                    index + 1,
+                    &HEADER[2..],
+                    line
+                ))
+            })
+        })
+        .collect()
+}
+
+fn parse_measurement(line: &str) -> Option<Measurement> {
+    let fields: Vec<_> = line.split_whitespace().collect();
+    let [day, phase, min, median, mean, stddev] = fields.as_slice() else {
+        return None;
+    };
+    let nanos = |s: &str| s.parse().ok().map(Duration::from_nanos);
+    Some(Measurement {
+        day: day.parse().ok()?,
+        phase: Phase::try_from(*phase).ok()?,
+        stats: Stats {
+            min: nanos(min)?,
+            median: nanos(median)?,
+            mean: nanos(mean)?,
+            stddev: nanos(stddev)?,
+        },
+    })
+}
+
+/// Prints the measurements and, given a baseline, the change of the median.
+/// Returns the number of phases whose median got slower by more than
+/// `threshold` percent.
+pub fn print_report(
+    measurements: &[Measurement],
+    baseline: Option<&[Measurement]>,
+    threshold: f64,
+) -> usize {
+    let mut regressions = 0;
+
+    print!(
+        "{:<4}{:<7}{:>11}{:>11}{:>11}{:>11}",
+        "Day", "Phase", "min", "median", "mean", "stddev"
+    );
+    if baseline.is_some() {
+        print!("{:>11}{:>9}", "baseline", "change");
+    }
+    println!();
+
+    for Measurement { day, phase, stats } in measurements {
+        print!(
+            "{:<4}{:<7}{:>11}{:>11}{:>11}{:>11}",
+            format!("{:02}", day),
+            phase.to_string(),
+            format_duration(stats.min),
+            format_duration(stats.median),
+            format_duration(stats.mean),
+            format_duration(stats.stddev)
+        );
+
+        let previous = baseline.and_then(|baseline| {
+            baseline
+                .iter()
+                .find(|previous| previous.day == *day && previous.phase == *phase)
+        });
+        if let Some(previous) = previous {
+            let before = previous.stats.median.as_nanos().max(1) as f64;
+            let change = (stats.median.as_nanos() as f64 - before) / before * 100.0;
+            print!(
+                "{:>11}{:>+8.1}%",
+                format_duration(previous.stats.median),
+                change
+            );
+            if change > threshold {
+                regressions += 1;
+                print!("  REGRESSION");
+            } else if change < -threshold {
+                print!("  improved");
+            }
+        }
+        println!();
+    }
+
+    regressions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_from_samples_should_summarize_samples() {
+        let samples = [4, 1, 3, 2].map(Duration::from_nanos);
+
+        let stats = Stats::from_samples(&samples);
+
+        assert_eq!(Duration::from_nanos(1), stats.min);
+        assert_eq!(Duration::from_nanos(2), stats.median);
+        assert_eq!(Duration::from_nanos(2), stats.mean);
+        assert_eq!(Duration::from_nanos(1), stats.stddev);
+    }
+
+    #[test]
+    fn parse_measurement_should_read_saved_line() {
+        let measurement = parse_measurement("11 part2 1 2 3 4").unwrap();
+
+        assert_eq!(11, measurement.day);
+        assert_eq!(Phase::Solve(Part::Two), measurement.phase);
+        assert_eq!(Duration::from_nanos(4), measurement.stats.stddev);
+    }
+}
@@ -0,0 +1,728 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use crate::answers::{self, Answers, ANSWERS_FILE};
+use crate::bench;
+use crate::error::{Error, Result, Strictness};
+use crate::execute::{self, Config, RunResult, Status};
+use crate::generate::{self, Rng};
+use crate::input::{self, InputSource};
+use crate::memory::{self, AllocStats};
+use crate::options::{self, Options};
+use crate::registry;
+use crate::repl::Repl;
+use crate::report;
+use crate::scaffold;
+use crate::serve::{Limits, Server};
+use crate::solution::{Answer, Part, Runner};
+use crate::trace::{self, Level};
+use crate::visualize::{self, Format};
+use crate::watch;
+
+pub const USAGE: &str = "\
+Usage: advent-of-code-rs <day> [part] [name=value...] [options]
+       advent-of-code-rs all [part] [options]
+       advent-of-code-rs verify [day|all] [part] [options]
+       advent-of-code-rs check [day|all] [options]
+       advent-of-code-rs bench <day|all> [part] [name=value...] [options]
+       advent-of-code-rs watch <day> [part] [name=value...] [options]
+       advent-of-code-rs repl [options]
+       advent-of-code-rs new <day> [title] [options]
+       advent-of-code-rs generate <day> [name=value...] [options]
+       advent-of-code-rs serve [name=value...] [options]
+       advent-of-code-rs visualize <day> [part] [name=value...] [options]
+       advent-of-code-rs --help [day]
+
+Days and their input generators may take options given as name=value, see
+--help <day>. Unless -i or -s is given, all, check and watch use every input
+set of each day. serve listens on 127.0.0.1 and solves the body of each
+POST /solve/<day>[/<part>]?name=value... request.
+
+Options:
+    -h, --help [day]       print this help, or the options of a day
+    -i, --input <path>     read the puzzle input from <path>, or from stdin for -
+    -s, --set <name>       use the named input set <dir>/<year>/dayNN/<name>.txt
+    -y, --year <year>      solve the days of <year> [default: 2022]
+        --input-dir <dir>  directory of the input sets [default: input]
+        --strict           report malformed input lines instead of skipping them
+    -j, --jobs <n>         days all runs at the same time, 0 for one per core [default: 1]
+        --answers <path>   expected answers file [default: <dir>/<year>/answers.txt]
+        --json             print one JSON object per day and part instead of text
+        --alloc            count the allocations of each run, needs the memory feature
+        --record           let verify store answers that have no expected value yet
+        --warmup <n>       untimed runs before benchmarking [default: 3]
+        --iterations <n>   timed runs per benchmarked phase [default: 20]
+        --save <path>      save the benchmark results to <path>
+        --compare <path>   compare the benchmark results against a saved run
+        --threshold <pct>  median slowdown reported as regression [default: 10]
+        --interval <ms>    how often watch checks the input files [default: 500]
+        --seed <n>         seed of the random input generate writes [default: 2022]
+        --port <n>         local port serve listens on [default: 8022]
+        --max-input <n>    largest input serve accepts, in bytes [default: 1048576]
+        --budget <ms>      time serve gives each request [default: 10000]
+        --format <format>  how visualize shows frames: ansi, ppm or svg [default: ansi]
+        --out <dir>        directory visualize writes images to [default: frames]
+        --fps <n>          frames per second of the ansi animation [default: 10]
+        --scale <px>       pixels per cell of the images [default: 8]
+        --every <n>        show only every nth frame [default: 1]
+    -v, --verbose          log what the days do to stderr, -vv or -vvv for more
+        --log <path>       write the log to <path> instead, with at least -v";
+
+/// Port of the serve command when none is given.
+pub const DEFAULT_PORT: u16 = 8022;
+
+/// Settings of the bench command.
+#[derive(Debug)]
+pub struct BenchArgs {
+    pub warmup: usize,
+    pub iterations: usize,
+    pub save: Option<PathBuf>,
+    pub compare: Option<PathBuf>,
+    pub threshold: f64,
+}
+
+impl Default for BenchArgs {
+    fn default() -> Self {
+        BenchArgs {
+            warmup: 3,
+            iterations: 20,
+            save: None,
+            compare: None,
+            threshold: 10.0,
+        }
+    }
+}
+
+/// Settings of the visualize command.
+#[derive(Debug)]
+pub struct VisualizeArgs {
+    pub format: Format,
+    pub out: PathBuf,
+    pub fps: f64,
+    pub scale: usize,
+    pub every: usize,
+}
+
+impl Default for VisualizeArgs {
+    fn default() -> Self {
+        VisualizeArgs {
+            format: Format::Ansi,
+            out: PathBuf::from("frames"),
+            fps: 10.0,
+            scale: 8,
+            every: 1,
+        }
+    }
+}
+
+/// The command line, split into positional arguments and options.
+#[derive(Debug)]
+pub struct Args {
+    pub positional: Vec<String>,
+    pub config: Config,
+    /// Whether the input was selected with -i or -s.
+    pub input_given: bool,
+    pub answers: Option<PathBuf>,
+    pub record: bool,
+    pub json: bool,
+    /// Whether allocations are counted.
+    pub alloc: bool,
+    pub help: bool,
+    pub bench: BenchArgs,
+    /// Polling interval of the watch command.
+    pub interval: Duration,
+    /// Number of days the all command runs at the same time.
+    pub jobs: usize,
+    /// Seed of the generate command.
+    pub seed: u64,
+    /// Local port of the serve command.
+    pub port: u16,
+    pub limits: Limits,
+    pub visualize: VisualizeArgs,
+    /// Number of -v given.
+    pub verbosity: usize,
+    /// File the log goes to instead of stderr.
+    pub log: Option<PathBuf>,
+}
+
+impl Args {
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Self> {
+        let mut parsed = Args {
+            positional: Vec::new(),
+            config: Config::default(),
+            input_given: false,
+            answers: None,
+            record: false,
+            json: false,
+            alloc: false,
+            help: false,
+            bench: BenchArgs::default(),
+            interval: Duration::from_millis(500),
+            jobs: 1,
+            seed: generate::DEFAULT_SEED,
+            port: DEFAULT_PORT,
+            limits: Limits::default(),
+            visualize: VisualizeArgs::default(),
+            verbosity: 0,
+            log: None,
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-i" | "--input" => {
+                    parsed.config.input = value_of(&arg, args.next())?.as_str().into();
+                    parsed.input_given = true;
+                }
+                "-s" | "--set" => {
+                    parsed.config.input = InputSource::Named(value_of(&arg, args.next())?);
+                    parsed.input_given = true;
+                }
+                "-y" | "--year" => parsed.config.year = parse_value(&arg, args.next())?,
+                "--input-dir" => parsed.config.input_dir = value_of(&arg, args.next())?.into(),
+                "--strict" => parsed.config.strictness = Strictness::Strict,
+                "-j" | "--jobs" => parsed.jobs = parse_value(&arg, args.next())?,
+                "--answers" => parsed.answers = Some(value_of(&arg, args.next())?.into()),
+                "--record" => parsed.record = true,
+                "--json" => parsed.json = true,
+                "--alloc" => parsed.alloc = true,
+                "-h" | "--help" => parsed.help = true,
+                "--warmup" => parsed.bench.warmup = parse_value(&arg, args.next())?,
+                "--iterations" => parsed.bench.iterations = parse_value(&arg, args.next())?,
+                "--save" => parsed.bench.save = Some(value_of(&arg, args.next())?.into()),
+                "--compare" => parsed.bench.compare = Some(value_of(&arg, args.next())?.into()),
+                "--threshold" => parsed.bench.threshold = parse_value(&arg, args.next())?,
+                "--seed" => parsed.seed = parse_value(&arg, args.next())?,
+                "--port" => parsed.port = parse_value(&arg, args.next())?,
+                "--max-input" => parsed.limits.max_input = parse_value(&arg, args.next())?,
+                "--budget" => {
+                    parsed.limits.time_budget =
+                        Duration::from_millis(parse_value(&arg, args.next())?)
+                }
+                "--format" => parsed.visualize.format = parse_value(&arg, args.next())?,
+                "--out" => parsed.visualize.out = value_of(&arg, args.next())?.into(),
+                "--fps" => parsed.visualize.fps = parse_value(&arg, args.next())?,
+                "--scale" => parsed.visualize.scale = parse_value(&arg, args.next())?,
+                "--every" => parsed.visualize.every = parse_value(&arg, args.next())?,
+                "--verbose" => parsed.verbosity += 1,
+                _ if arg.len() > 1
+                    && arg.starts_with('-')
+                    && arg[1..].bytes().all(|b| b == b'v') =>
+                {
+                    parsed.verbosity += arg.len() - 1
+                }
+                "--log" => parsed.log = Some(value_of(&arg, args.next())?.into()),
+                "--interval" => {
+                    parsed.interval = Duration::from_millis(parse_value(&arg, args.next())?)
+                }
+                _ if arg.starts_with("--") => {
+                    return Err(usage_error(format!("Unknown option {}", arg)))
+                }
+                _ => match options::split_assignment(&arg) {
+                    Some(option) => parsed.config.options.push(option),
+                    None => parsed.positional.push(arg),
+                },
+            }
+        }
+
+        // new starts the first day of a year as well.
+        let new = parsed
+            .positional
+            .first()
+            .is_some_and(|command| command == "new");
+        if !new && !registry::years().any(|year| year == parsed.config.year) {
+            return Err(usage_error(format!(
+                "Unknown year {}. Available years: {}",
+                parsed.config.year,
+                registry::available_years()
+            )));
+        }
+        Ok(parsed)
+    }
+
+    /// The most detailed events logged, if any.
+    pub fn trace_level(&self) -> Option<Level> {
+        match (Level::from_verbosity(self.verbosity), &self.log) {
+            (None, Some(_)) => Some(Level::Info),
+            (level, _) => level,
+        }
+    }
+
+    pub fn answers_path(&self) -> PathBuf {
+        self.answers.clone().unwrap_or_else(|| {
+            input::year_dir(&self.config.input_dir, self.config.year).join(ANSWERS_FILE)
+        })
+    }
+
+    /// The part selected by the positional argument at `index`, or both parts if
+    /// it is missing.
+    pub fn parts(&self, index: usize) -> Result<Vec<Part>> {
+        match self.positional.get(index) {
+            Some(part) => Ok(vec![
+                Part::try_from(part.as_str()).map_err(|_| Error::UnknownPart(part.clone()))?
+            ]),
+            None => Ok(Part::ALL.to_vec()),
+        }
+    }
+}
+
+fn value_of(option: &str, value: Option<String>) -> Result<String> {
+    value.ok_or_else(|| usage_error(format!("Missing value for {}", option)))
+}
+
+fn parse_value<T: FromStr>(option: &str, value: Option<String>) -> Result<T> {
+    let value = value_of(option, value)?;
+    value
+        .parse()
+        .map_err(|_| usage_error(format!("Invalid value {} for {}", value, option)))
+}
+
+pub fn usage_error(message: String) -> Error {
+    Error::Usage(format!("{}\n\n{}", message, USAGE))
+}
+
+/// Runs the command given by the arguments, without the program name.
+pub fn run<I: Iterator<Item = String>>(args: I) -> Result<()> {
+    let args = Args::parse(args)?;
+    if args.help {
+        return help(&args);
+    }
+    if args.alloc && !memory::enable() {
+        return Err(Error::Failed(
+            "Counting allocations is unavailable, build with `--features memory`".into(),
+        ));
+    }
+    trace::init(args.trace_level(), args.log.as_deref())?;
+
+    match args.positional.first().map(String::as_str) {
+        Some("all") => run_all(&args),
+        Some("verify") => verify(&args),
+        Some("check") => check(&args),
+        Some("bench") => bench(&args),
+        Some("watch") => watch(&args),
+        Some("repl") => Ok(Repl::new(args.config).run(io::stdin().lock(), io::stdout())?),
+        Some("new") => new_day(&args),
+        Some("generate") => generate(&args),
+        Some("serve") => serve(&args),
+        Some("visualize") => visualize(&args),
+        Some(day) => run_day(&args, day),
+        None => Err(usage_error(format!(
+            "Missing day. Available days: {}",
+            registry::available_days(args.config.year)
+        ))),
+    }
+}
+
+fn find_day(args: &Args, day: &str) -> Result<&'static dyn Runner> {
+    let year = args.config.year;
+    day.parse::<u8>()
+        .ok()
+        .and_then(|day| registry::get(year, day))
+        .ok_or_else(|| Error::UnknownDay {
+            year,
+            day: day.to_owned(),
+        })
+}
+
+fn help(args: &Args) -> Result<()> {
+    match args.positional.first() {
+        Some(day) => {
+            let runner = find_day(args, day)?;
+            println!("Day {:02}: {}\n", runner.day(), runner.title());
+            match runner.options() {
+                [] => println!("This day has no options."),
+                specs => println!("{}", options::help("Options", specs)),
+            }
+            if !runner.generator_options().is_empty() {
+                println!(
+                    "\n{}",
+                    options::help("Input generator options", runner.generator_options())
+                );
+            }
+        }
+        None => println!("{}", USAGE),
+    }
+    Ok(())
+}
+
+/// Rejects unknown options and invalid values before anything is run.
+fn check_options(args: &Args, runners: &[&'static dyn Runner], parts: &[Part]) -> Result<()> {
+    for &runner in runners {
+        for &part in parts {
+            args.config.options(runner, part)?;
+        }
+    }
+    Ok(())
+}
+
+fn run_day(args: &Args, day: &str) -> Result<()> {
+    let runner = find_day(args, day)?;
+    let parts = args.parts(1)?;
+    check_options(args, &[runner], &parts)?;
+
+    if args.json {
+        let results =
+            execute::with_silent_panics(|| execute::run_day(runner, &parts, &args.config));
+        report::print_json(&results, &args.config);
+        return check_results(&results);
+    }
+
+    let input = args.config.read_input(runner.day())?;
+    let (input, parse_memory) = memory::measure(|| runner.parse(&input, args.config.strictness));
+    let input = input?;
+
+    println!("Day {:02}: {}", runner.day(), runner.title());
+    if let Some(memory) = parse_memory {
+        println!("Parsing: {}", format_memory(memory));
+    }
+    for part in parts {
+        let options = args.config.options(runner, part)?;
+        let (answer, solve_memory) = memory::measure(|| input.solve(part, &options));
+        println!("{}", runner.describe(part, &options, &answer));
+        if let Some(memory) = solve_memory {
+            println!("    {}", format_memory(memory));
+        }
+    }
+
+    Ok(())
+}
+
+fn format_memory(memory: AllocStats) -> String {
+    format!(
+        "{} allocations, {} allocated, {} peak",
+        memory.allocations,
+        report::format_bytes(memory.bytes),
+        report::format_bytes(memory.peak)
+    )
+}
+
+fn run_all(args: &Args) -> Result<()> {
+    let parts = args.parts(1)?;
+    let runners: Vec<_> = registry::days(args.config.year).collect();
+    check_options(args, &runners, &parts)?;
+
+    let days = day_inputs(args, &runners)?;
+    let jobs = match args.jobs {
+        0 => thread::available_parallelism().map_or(1, usize::from),
+        jobs => jobs,
+    };
+    let runs = execute::with_silent_panics(|| execute::run_days(&days, &parts, &args.config, jobs));
+
+    let results: Vec<_> = runs.into_iter().flatten().collect();
+    if args.json {
+        report::print_json(&results, &args.config);
+    } else {
+        report::print_table(&results);
+    }
+
+    check_results(&results)
+}
+
+/// Each day with the input selected by -i or -s, or with every input set it has.
+fn day_inputs(
+    args: &Args,
+    runners: &[&'static dyn Runner],
+) -> Result<Vec<(&'static dyn Runner, InputSource)>> {
+    let mut days = Vec::new();
+    for &runner in runners {
+        if args.input_given {
+            days.push((runner, args.config.input.clone()));
+            continue;
+        }
+        let sets = input::sets(&args.config.input_dir, args.config.year, runner.day())?;
+        if sets.is_empty() {
+            // Reported as a missing input of the default set.
+            days.push((runner, InputSource::default()));
+        }
+        days.extend(sets.into_iter().map(|set| (runner, set)));
+    }
+    Ok(days)
+}
+
+fn check_results(results: &[RunResult]) -> Result<()> {
+    match results
+        .iter()
+        .filter(|result| result.status != Status::Ok)
+        .count()
+    {
+        0 => Ok(()),
+        failed => Err(Error::Failed(format!("{} parts failed", failed))),
+    }
+}
+
+/// The days selected by the positional argument at `index`: one day, or all of
+/// them for `all` or if it is missing.
+fn selected_days(args: &Args, index: usize) -> Result<Vec<&'static dyn Runner>> {
+    match args.positional.get(index).map(String::as_str) {
+        None | Some("all") => Ok(registry::days(args.config.year).collect()),
+        Some(day) => Ok(vec![find_day(args, day)?]),
+    }
+}
+
+fn verify(args: &Args) -> Result<()> {
+    let runners = selected_days(args, 1)?;
+    let parts = args.parts(2)?;
+    check_options(args, &runners, &parts)?;
+    let input_name = args.config.input.to_string();
+    let answers_path = args.answers_path();
+    let mut answers = Answers::load(&answers_path)?;
+
+    let results: Vec<_> = execute::with_silent_panics(|| {
+        runners
+            .iter()
+            .flat_map(|&runner| {
+                execute::run_day(runner, &parts, &args.config)
+                    .into_iter()
+                    .map(move |result| (runner, result))
+            })
+            .collect()
+    });
+
+    let (mut correct, mut wrong, mut missing, mut failed, mut recorded) = (0, 0, 0, 0, 0);
+    for (runner, result) in &results {
+        let options = args
+            .config
+            .options(*runner, result.part)?
+            .changed(runner.options(), result.part);
+        let mut label = format!("Day {:02} part {} ({}", result.day, result.part, input_name);
+        for option in &options {
+            label += &format!(", {}", option);
+        }
+        label.push(')');
+        let actual = match (&result.status, &result.answer) {
+            (Status::Ok, Some(answer)) => answer.to_string(),
+            (status, _) => {
+                failed += 1;
+                println!("{}: {}", label, status);
+                continue;
+            }
+        };
+
+        match answers.get(result.day, result.part, &input_name, &options) {
+            Some(expected) if expected == actual => {
+                correct += 1;
+                println!("{}: ok", label);
+            }
+            Some(expected) => {
+                wrong += 1;
+                println!("{}: MISMATCH", label);
+                for line in answers::diff(expected, &actual).lines() {
+                    println!("    {}", line);
+                }
+            }
+            None if args.record && result.answer != Some(Answer::None) => {
+                recorded += 1;
+                answers.insert(result.day, result.part, &input_name, &options, &actual);
+                println!("{}: recorded {}", label, actual);
+            }
+            None => {
+                missing += 1;
+                println!("{}: no expected answer, got {}", label, actual);
+            }
+        }
+    }
+
+    if recorded > 0 {
+        answers.save(&answers_path)?;
+    }
+
+    println!(
+        "\n{} correct, {} wrong, {} without expected answer, {} failed, {} recorded",
+        correct, wrong, missing, failed, recorded
+    );
+    match wrong + failed {
+        0 => Ok(()),
+        _ => Err(Error::Failed(format!(
+            "Verification against {} failed",
+            answers_path.display()
+        ))),
+    }
+}
+
+/// Validates the shape of the inputs without solving anything, reporting every
+/// problem found.
+fn check(args: &Args) -> Result<()> {
+    let runners = selected_days(args, 1)?;
+    let mut failed = 0;
+    for (runner, input) in day_inputs(args, &runners)? {
+        let config = Config {
+            input,
+            ..args.config.clone()
+        };
+        let label = format!("Day {:02} ({})", runner.day(), config.input);
+        let problems = match config.read_input(runner.day()) {
+            Ok(text) => runner.check(&text),
+            Err(err) => {
+                failed += 1;
+                println!("{}: {}", label, err);
+                continue;
+            }
+        };
+        match problems.len() {
+            0 => println!("{}: ok", label),
+            count => {
+                failed += 1;
+                println!("{}: {} problems", label, count);
+                for problem in problems {
+                    for line in problem.to_string().lines() {
+                        println!("    {}", line);
+                    }
+                }
+            }
+        }
+    }
+
+    match failed {
+        0 => Ok(()),
+        _ => Err(Error::Failed(format!("{} inputs have problems", failed))),
+    }
+}
+
+fn bench(args: &Args) -> Result<()> {
+    if args.positional.len() < 2 {
+        return Err(usage_error("Missing day to benchmark".into()));
+    }
+    let runners = selected_days(args, 1)?;
+    let parts = args.parts(2)?;
+    check_options(args, &runners, &parts)?;
+    let settings = &args.bench;
+    let baseline = settings.compare.as_deref().map(bench::load).transpose()?;
+
+    let mut measurements = Vec::new();
+    for runner in runners {
+        measurements.extend(bench::bench_day(
+            runner,
+            &parts,
+            &args.config,
+            settings.warmup,
+            settings.iterations,
+        )?);
+    }
+
+    println!(
+        "{} iterations after {} warm-up runs",
+        settings.iterations, settings.warmup
+    );
+    let regressions = bench::print_report(&measurements, baseline.as_deref(), settings.threshold);
+
+    if let Some(path) = &settings.save {
+        bench::save(path, &measurements)?;
+    }
+
+    match regressions {
+        0 => Ok(()),
+        _ => Err(Error::Failed(format!(
+            "{} phases regressed by more than {}%",
+            regressions, settings.threshold
+        ))),
+    }
+}
+
+fn watch(args: &Args) -> Result<()> {
+    let day = args
+        .positional
+        .get(1)
+        .ok_or_else(|| usage_error("Missing day to watch".into()))?;
+    let runner = find_day(args, day)?;
+    let parts = args.parts(2)?;
+    check_options(args, &[runner], &parts)?;
+
+    watch::watch(runner, &parts, &args.config, args.interval, || {
+        let days = day_inputs(args, &[runner])?;
+        Ok(days.into_iter().map(|(_, input)| input).collect())
+    })
+}
+
+fn new_day(args: &Args) -> Result<()> {
+    let day = args
+        .positional
+        .get(1)
+        .ok_or_else(|| usage_error("Missing day to create".into()))?;
+    let day = day
+        .parse()
+        .map_err(|_| usage_error(format!("Invalid day {}", day)))?;
+    let title = args.positional[2..].join(" ");
+    let title = match title.as_str() {
+        "" => format!("Day {}", day),
+        _ => title,
+    };
+
+    let written = scaffold::new_day(
+        Path::new("."),
+        &args.config.input_dir,
+        args.config.year,
+        day,
+        &title,
+    )?;
+    for path in written {
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn generate(args: &Args) -> Result<()> {
+    let day = args
+        .positional
+        .get(1)
+        .ok_or_else(|| usage_error("Missing day to generate an input for".into()))?;
+    let runner = find_day(args, day)?;
+    let specs = runner.generator_options();
+    let options = Options::resolve(runner.day(), specs, Part::One, &args.config.options)?;
+
+    let input = runner
+        .generate(&mut Rng::new(args.seed), &options)
+        .ok_or_else(|| Error::Failed(format!("Day {:02} has no input generator", runner.day())))?;
+    print!("{}", input);
+    Ok(())
+}
+
+fn serve(args: &Args) -> Result<()> {
+    let server = Server::bind(
+        ("127.0.0.1", args.port),
+        args.config.clone(),
+        args.limits.clone(),
+    )?;
+    println!("Listening on http://{}", server.local_addr()?);
+    execute::with_silent_panics(|| server.run())?;
+    Ok(())
+}
+
+fn visualize(args: &Args) -> Result<()> {
+    let day = args
+        .positional
+        .get(1)
+        .ok_or_else(|| usage_error("Missing day to visualize".into()))?;
+    let runner = find_day(args, day)?;
+    let part = args.parts(2)?[0];
+    let settings = &args.visualize;
+    if settings.fps.is_nan() || settings.fps <= 0.0 || settings.scale == 0 || settings.every == 0 {
+        return Err(usage_error(
+            "--fps, --scale and --every must be greater than 0".into(),
+        ));
+    }
+    let options = args.config.options(runner, part)?;
+
+    let input = args.config.read_input(runner.day())?;
+    let input = runner.parse(&input, args.config.strictness)?;
+    let frames = input
+        .visualize(part, &options)
+        .ok_or_else(|| Error::Failed(format!("Day {:02} has no visualization", runner.day())))?;
+    let frames = Box::new(frames.step_by(settings.every));
+
+    match settings.format {
+        Format::Ansi => {
+            visualize::play(frames, settings.fps, io::stdout().lock())?;
+        }
+        format => {
+            let written = visualize::write_frames(frames, &settings.out, format, settings.scale)?;
+            println!(
+                "wrote {} frames to {}",
+                written.len(),
+                settings.out.display()
+            );
+        }
+    }
+    Ok(())
+}
@@ -1,48 +1,133 @@
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
 
-pub fn run(args: &[String]) -> std::io::Result<()> {
-    println!("day 01");
-    let file = File::open("input/day01.txt")?;
-    let reader = BufReader::new(file);
+use crate::error::{Result, Strictness};
+use crate::generate::Rng;
+use crate::options::{OptionKind, OptionSpec, Options};
+use crate::parse::{self, number};
+use crate::solution::{Answer, Part, Solution};
+use crate::trace::{event, Level};
 
-    let number_of_top_elves = args.get(0).and_then(|s| s.parse::<i32>().ok()).unwrap_or(1);
+pub struct Day01;
 
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Calorie Counting";
+
+    /// The total calories carried by each elf.
+    type Input = Vec<i32>;
+
+    const OPTIONS: &'static [OptionSpec] = &[OptionSpec {
+        name: "top",
+        kind: OptionKind::Range {
+            min: 1,
+            max: 10_000,
+        },
+        defaults: ["1", "3"],
+        help: "number of elves carrying the most calories to add up",
+    }];
+
+    const GENERATOR: &'static [OptionSpec] = &[
+        OptionSpec {
+            name: "elves",
+            kind: OptionKind::Range {
+                min: 1,
+                max: 100_000,
+            },
+            defaults: ["250", "250"],
+            help: "number of elves",
+        },
+        OptionSpec {
+            name: "items",
+            kind: OptionKind::Range { min: 1, max: 100 },
+            defaults: ["15", "15"],
+            help: "most food items carried by one elf",
+        },
+    ];
+
+    fn parse_with(input: &str, strictness: Strictness) -> Result<Self::Input> {
+        let mut elves = Vec::new();
+
+        for section in parse::sections(input) {
+            let mut calories_of_elf = 0;
+            for line in &section.lines {
+                match line.parse(number::<i32>()) {
+                    Ok(calories) => calories_of_elf += calories,
+                    Err(column) => strictness.check(line.error(
+                        Self::DAY,
+                        column,
+                        "a number of calories or an empty line",
+                    ))?,
+                }
+            }
+            elves.push(calories_of_elf);
+        }
+
+        Ok(elves)
+    }
+
+    fn generate(rng: &mut Rng, options: &Options) -> Option<String> {
+        let max_items = options.integer("items") as u64;
+        let elves: Vec<String> = (0..options.integer("elves"))
+            .map(|_| {
+                (0..rng.range(1..=max_items))
+                    .map(|_| format!("{}\n", rng.range(1000..=60000)))
+                    .collect()
+            })
+            .collect();
+        Some(elves.join("\n"))
+    }
+
+    fn part1(input: &Self::Input, options: &Options) -> Answer {
+        top_elves_calories(input, options.integer("top") as usize).into()
+    }
+
+    fn part2(input: &Self::Input, options: &Options) -> Answer {
+        top_elves_calories(input, options.integer("top") as usize).into()
+    }
+
+    fn describe(_part: Part, options: &Options, answer: &Answer) -> String {
+        format!(
+            "The {} elves with the most calories carry {}",
+            options.integer("top"),
+            answer
+        )
+    }
+}
+
+fn top_elves_calories(elves: &[i32], number_of_top_elves: usize) -> i32 {
     let mut top_elves_calories = BinaryHeap::new();
     for _ in 0..number_of_top_elves {
         top_elves_calories.push(Reverse(0));
     }
 
-    reader
-        .lines()
-        .map_while(Result::ok)
-        .map(|line| line.parse::<i32>().ok())
-        .fold(
-            0,
-            |calories_of_current_elf, maybe_calories| match maybe_calories {
-                Some(calories) => calories_of_current_elf + calories,
-                _ => match top_elves_calories.peek() {
-                    Some(Reverse(min)) => {
-                        if *min < calories_of_current_elf {
-                            top_elves_calories.pop();
-                            top_elves_calories.push(Reverse(calories_of_current_elf));
-                            0
-                        } else {
-                            0
-                        }
-                    }
-                    _ => calories_of_current_elf,
-                },
-            },
-        );
+    for (index, &calories) in elves.iter().enumerate() {
+        event!(Level::Trace, "elf", elf = index + 1, calories = calories);
+        if let Some(Reverse(min)) = top_elves_calories.peek() {
+            if *min < calories {
+                top_elves_calories.pop();
+                top_elves_calories.push(Reverse(calories));
+            }
+        }
+    }
 
-    let calories = top_elves_calories.iter().fold(0, |a, b| a + b.0);
-    println!(
-        "The {} elves with the most calories carry {}",
-        number_of_top_elves, calories
+    let total = top_elves_calories.iter().fold(0, |a, b| a + b.0);
+    event!(
+        Level::Info,
+        "top",
+        elves = number_of_top_elves,
+        calories = total
     );
-    Ok(())
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::example_tests;
+
+    example_tests! {
+        Day01;
+        example: part1 = 24000, part2 = 45000;
+    }
 }
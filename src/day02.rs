@@ -1,65 +1,119 @@
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
-
-pub fn run(args: &[String]) -> std::io::Result<()> {
-    let file = File::open("input/day02.txt")?;
-    let reader = BufReader::new(file);
-
-    let strategy = args
-        .get(0)
-        .and_then(|s| s.try_into().ok())
-        .unwrap_or(Strategy::Answer);
-
-    let score: usize = reader
-        .lines()
-        .map_while(Result::ok)
-        .filter_map(|s| {
-            let mut chars = s.chars();
-            let opponent: Option<Move> = chars.next().and_then(|c| c.try_into().ok());
-
-            // Skip the space
-            _ = chars.next();
-
-            let player: Option<Move> = chars.next().and_then(|c| match strategy {
-                Strategy::Answer => c.try_into().ok(),
+use crate::error::{Result, Strictness};
+use crate::generate::Rng;
+use crate::options::{OptionKind, OptionSpec, Options};
+use crate::parse::{self, literal, one_of, Parser};
+use crate::solution::{Answer, Part, Solution};
+use crate::trace::{event, Level};
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Rock Paper Scissors";
+
+    /// The opponent's move and the unexplained second column of each round.
+    type Input = Vec<(Move, char)>;
+
+    const OPTIONS: &'static [OptionSpec] = &[OptionSpec {
+        name: "strategy",
+        kind: OptionKind::Choice(&["answer", "outcome"]),
+        defaults: ["answer", "outcome"],
+        help: "whether the second column is the move to play or the outcome to reach",
+    }];
+
+    const GENERATOR: &'static [OptionSpec] = &[OptionSpec {
+        name: "rounds",
+        kind: OptionKind::Range {
+            min: 1,
+            max: 1_000_000,
+        },
+        defaults: ["2500", "2500"],
+        help: "number of rounds",
+    }];
+
+    fn parse_with(input: &str, strictness: Strictness) -> Result<Self::Input> {
+        let mut rounds = Vec::new();
+        for line in parse::lines(input) {
+            match line.parse(round()) {
+                Ok(round) => rounds.push(round),
+                Err(column) => {
+                    strictness.check(line.error(Self::DAY, column, "`<A|B|C> <X|Y|Z>`"))?
+                }
+            }
+        }
+        Ok(rounds)
+    }
+
+    fn generate(rng: &mut Rng, options: &Options) -> Option<String> {
+        let rounds = (0..options.integer("rounds"))
+            .map(|_| {
+                format!(
+                    "{} {}\n",
+                    rng.pick(&['A', 'B', 'C']),
+                    rng.pick(&['X', 'Y', 'Z'])
+                )
+            })
+            .collect();
+        Some(rounds)
+    }
+
+    fn part1(input: &Self::Input, options: &Options) -> Answer {
+        get_score(input, Strategy::from(options)).into()
+    }
+
+    fn part2(input: &Self::Input, options: &Options) -> Answer {
+        get_score(input, Strategy::from(options)).into()
+    }
+
+    fn describe(_part: Part, _options: &Options, answer: &Answer) -> String {
+        format!("Scored: {}", answer)
+    }
+}
+
+fn round<'a>() -> impl Parser<'a, (Move, char)> {
+    one_of(&[("A", Move::Rock), ("B", Move::Paper), ("C", Move::Scissors)])
+        .skip(literal(" "))
+        .and(one_of(&[("X", 'X'), ("Y", 'Y'), ("Z", 'Z')]))
+}
+
+fn get_score(rounds: &[(Move, char)], strategy: Strategy) -> usize {
+    rounds
+        .iter()
+        .map(|&(opponent, c)| {
+            let player = match strategy {
+                Strategy::Answer => Move::try_from(c).unwrap(),
                 Strategy::Outcome => match c {
                     // player loses
                     'X' => match opponent {
-                        Some(Move::Scissors) => Some(Move::Paper),
-                        Some(Move::Paper) => Some(Move::Rock),
-                        Some(Move::Rock) => Some(Move::Scissors),
-                        _ => None,
+                        Move::Scissors => Move::Paper,
+                        Move::Paper => Move::Rock,
+                        Move::Rock => Move::Scissors,
                     },
 
                     // player draws
                     'Y' => opponent,
 
                     // player wins
-                    'Z' => match opponent {
-                        Some(Move::Paper) => Some(Move::Scissors),
-                        Some(Move::Rock) => Some(Move::Paper),
-                        Some(Move::Scissors) => Some(Move::Rock),
-                        _ => None,
+                    _ => match opponent {
+                        Move::Paper => Move::Scissors,
+                        Move::Rock => Move::Paper,
+                        Move::Scissors => Move::Rock,
                     },
-                    _ => None,
                 },
-            });
-
-            match (opponent, player) {
-                (Some(x), Some(y)) => Some((x, y)),
-                _ => None,
-            }
+            };
+            (opponent, player)
         })
         .map(get_player_score_for_game)
-        .sum();
-
-    println!("Scored: {}", score);
-    Ok(())
+        .enumerate()
+        .map(|(index, score)| {
+            event!(Level::Debug, "round", round = index + 1, score = score);
+            score
+        })
+        .sum()
 }
 
 #[derive(PartialEq, Clone, Copy)]
-enum Move {
+pub enum Move {
     Rock,
     Paper,
     Scissors,
@@ -70,20 +124,18 @@ enum Strategy {
     Outcome,
 }
 
-impl TryFrom<&String> for Strategy {
-    type Error = ();
-    fn try_from(s: &String) -> Result<Self, Self::Error> {
-        match s.as_str() {
-            "1" => Ok(Self::Answer),
-            "2" => Ok(Self::Outcome),
-            _ => Err(()),
+impl From<&Options> for Strategy {
+    fn from(options: &Options) -> Self {
+        match options.choice("strategy") {
+            "answer" => Strategy::Answer,
+            _ => Strategy::Outcome,
         }
     }
 }
 
 impl TryFrom<char> for Move {
     type Error = ();
-    fn try_from(c: char) -> Result<Self, Self::Error> {
+    fn try_from(c: char) -> std::result::Result<Self, Self::Error> {
         match c {
             'A' | 'X' => Ok(Move::Rock),
             'B' | 'Y' => Ok(Move::Paper),
@@ -109,3 +161,14 @@ fn get_player_score_for_game(game: (Move, Move)) -> usize {
     };
     shape_score + win_score
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::example_tests;
+
+    example_tests! {
+        Day02;
+        example: part1 = 15, part2 = 12;
+    }
+}
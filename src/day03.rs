@@ -1,10 +1,14 @@
 use itertools::Itertools;
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
 
-fn get_item_in_both(str: String) -> Option<char> {
+use crate::error::{Result, Strictness};
+use crate::generate::Rng;
+use crate::options::{OptionKind, OptionSpec, Options};
+use crate::parse::{self, take_while1, Parser};
+use crate::solution::{Answer, Part, Solution};
+use crate::trace::{event, Level};
+
+fn get_item_in_both(str: &str) -> Option<char> {
     let l = str.len();
 
     let mut first_half = HashSet::new();
@@ -23,53 +27,141 @@ fn get_priority(c: char) -> Option<i32> {
     }
 }
 
-fn run_part1(reader: BufReader<File>) -> std::io::Result<()> {
-    let sum_of_priorities: i32 = reader
-        .lines()
-        .filter_map(|x| match x {
-            Ok(x) => Some(x),
-            _ => None,
-        })
-        .filter_map(get_item_in_both)
-        .filter_map(get_priority)
-        .sum();
-
-    println!("The sum of priorities is {}", sum_of_priorities);
-    Ok(())
-}
+pub struct Day03;
 
-fn run_part2(reader: BufReader<File>) -> std::io::Result<()> {
-    let sum: i32 = reader
-        .lines()
-        .map_while(Result::ok)
-        .chunks(3)
-        .into_iter()
-        .map(|chunk| {
-            chunk
-                .map(|x| x.chars().collect::<HashSet<char>>())
-                .reduce(|a, b| a.intersection(&b).copied().collect::<HashSet<char>>())
-                .and_then(|s| s.into_iter().collect::<Vec<char>>().first().copied())
-                .and_then(get_priority)
-                .unwrap_or(0)
-        })
-        .sum();
-
-    println!("Sum of priorities {:?}", sum);
-    Ok(())
-}
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Rucksack Reorganization";
+
+    /// The contents of each rucksack.
+    type Input = Vec<String>;
 
-pub fn run(args: &[String]) -> std::io::Result<()> {
-    let file = File::open("input/day03.txt")?;
-    let reader = BufReader::new(file);
+    const GENERATOR: &'static [OptionSpec] = &[
+        OptionSpec {
+            name: "groups",
+            kind: OptionKind::Range {
+                min: 1,
+                max: 100_000,
+            },
+            defaults: ["100", "100"],
+            help: "number of groups of three elves",
+        },
+        OptionSpec {
+            name: "items",
+            kind: OptionKind::Range { min: 2, max: 100 },
+            defaults: ["16", "16"],
+            help: "most items in one compartment",
+        },
+    ];
 
-    match args.get(0).map(|s| s.parse::<i32>()) {
-        Some(Ok(1)) => run_part1(reader),
-        Some(Ok(2)) => run_part2(reader),
-        _ => {
-            println!("Unknown part");
-            Ok(())
+    fn parse_with(input: &str, strictness: Strictness) -> Result<Self::Input> {
+        let items = || {
+            take_while1(|c| c.is_ascii_alphabetic()).try_map(|items| {
+                let even = items.len() % 2 == 0;
+                even.then_some(items)
+            })
+        };
+        let mut rucksacks = Vec::new();
+        for line in parse::lines(input) {
+            match line.parse(items()) {
+                Ok(items) => rucksacks.push(items.to_string()),
+                Err(column) => strictness.check(line.error(
+                    Self::DAY,
+                    column,
+                    "an even, non-zero number of items a-z or A-Z",
+                ))?,
+            }
         }
-    }?;
+        Ok(rucksacks)
+    }
+
+    /// Every rucksack has exactly one item in both compartments and every group
+    /// exactly one item in all three rucksacks, its badge.
+    fn generate(rng: &mut Rng, options: &Options) -> Option<String> {
+        let max_items = options.integer("items") as u64;
+        let mut rucksacks = String::new();
+        for _ in 0..options.integer("groups") {
+            let mut items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+            rng.shuffle(&mut items);
+            let (badge, pools) = items.split_first().expect("there are 52 items");
+            // Each rucksack of the group takes its items from its own 17.
+            for pool in pools.chunks(17) {
+                let (shared, pool) = pool.split_first().expect("pools have 17 items");
+                let (left_pool, right_pool) = pool.split_at(8);
+                let size = rng.range(2..=max_items) as usize;
+
+                let mut left = vec![*shared, *badge];
+                left.extend((2..size).map(|_| *rng.pick(left_pool)));
+                rng.shuffle(&mut left);
+                let mut right = vec![*shared];
+                right.extend((1..size).map(|_| *rng.pick(right_pool)));
+                rng.shuffle(&mut right);
+
+                rucksacks.extend(left.into_iter().chain(right));
+                rucksacks.push('\n');
+            }
+        }
+        Some(rucksacks)
+    }
+
+    fn part1(input: &Self::Input, _options: &Options) -> Answer {
+        let sum_of_priorities: i32 = input
+            .iter()
+            .filter_map(|x| get_item_in_both(x))
+            .filter_map(|item| {
+                let priority = get_priority(item)?;
+                event!(Level::Debug, "misplaced", item = item, priority = priority);
+                Some(priority)
+            })
+            .sum();
+
+        sum_of_priorities.into()
+    }
 
-    Ok(())
+    fn part2(input: &Self::Input, _options: &Options) -> Answer {
+        let sum: i32 = input
+            .iter()
+            .chunks(3)
+            .into_iter()
+            .map(|chunk| {
+                chunk
+                    .map(|x| x.chars().collect::<HashSet<char>>())
+                    .reduce(|a, b| a.intersection(&b).copied().collect::<HashSet<char>>())
+                    .and_then(|s| s.into_iter().collect::<Vec<char>>().first().copied())
+                    .and_then(|badge| {
+                        let priority = get_priority(badge)?;
+                        event!(Level::Debug, "badge", item = badge, priority = priority);
+                        Some(priority)
+                    })
+                    .unwrap_or(0)
+            })
+            .sum();
+
+        sum.into()
+    }
+
+    fn describe(part: Part, _options: &Options, answer: &Answer) -> String {
+        match part {
+            Part::One => format!("The sum of priorities is {}", answer),
+            Part::Two => format!("Sum of priorities {}", answer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::example_tests;
+
+    example_tests! {
+        Day03;
+        example: part1 = 157, part2 = 70;
+    }
+
+    #[test]
+    fn parse_should_skip_invalid_rucksacks_when_lenient() {
+        let rucksacks = Day03::parse("abcb\nabc\n\nABCA\n").unwrap();
+
+        assert_eq!(vec!["abcb", "ABCA"], rucksacks);
+    }
 }
@@ -1,81 +1,145 @@
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
-
-fn parse_range(s: &str) -> Option<(i32, i32)> {
-    let numbers: Vec<_> = s.split('-').map(str::parse::<i32>).collect();
-    if numbers.len() == 2 {
-        if let (Some(Ok(start)), Some(Ok(end))) = (numbers.get(0), numbers.get(1)) {
-            return Some((*start, *end));
-        }
-    }
-    None
+use crate::error::{Result, Strictness};
+use crate::generate::Rng;
+use crate::options::{OptionKind, OptionSpec, Options};
+use crate::parse::{self, literal, number, Parser};
+use crate::solution::{Answer, Part, Solution};
+use crate::trace::{event, Level};
+
+/// The first and last section of an elf's assignment.
+type Assignment = (i32, i32);
+
+type Pair = (Assignment, Assignment);
+
+fn assignment<'a>() -> impl Parser<'a, Assignment> {
+    number().skip(literal("-")).and(number())
 }
 
-fn parse_line(line: String) -> Option<((i32, i32), (i32, i32))> {
-    let ranges: Vec<_> = line.split(',').map(parse_range).collect();
-    if ranges.len() == 2 {
-        if let (Some((a, b)), Some((c, d))) = (ranges[0], ranges[1]) {
-            return Some(((a, b), (c, d)));
-        }
-    }
-    None
+fn pair<'a>() -> impl Parser<'a, Pair> {
+    assignment().skip(literal(",")).and(assignment())
 }
 
-fn either_fully_contained(ranges: ((i32, i32), (i32, i32))) -> bool {
+fn either_fully_contained(ranges: Pair) -> bool {
     let ((a, b), (c, d)) = ranges;
     (a <= c && c <= d && d <= b) || (c <= a && a <= b && b <= d)
 }
 
-fn overlap(ranges: ((i32, i32), (i32, i32))) -> bool {
+fn overlap(ranges: Pair) -> bool {
     let ((a, b), (c, d)) = ranges;
     (a <= c && c <= b) || (a <= d && d <= b) || (c <= a && a <= d) || (c <= b && b <= d)
 }
 
-fn run_part1(reader: BufReader<File>) -> std::io::Result<()> {
-    let number_fully_contained = reader
-        .lines()
-        .map_while(Result::ok)
-        .filter_map(parse_line)
-        .map(either_fully_contained)
-        .fold(0, |a, b| a + if b { 1 } else { 0 });
+pub struct Day04;
 
-    println!(
-        "The number of fully contained ranges is {}",
-        number_fully_contained
-    );
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Camp Cleanup";
 
-    Ok(())
-}
+    /// The pair of section assignments on each line.
+    type Input = Vec<Pair>;
 
-fn run_part2(reader: BufReader<File>) -> std::io::Result<()> {
-    let number_fully_contained = reader
-        .lines()
-        .map_while(Result::ok)
-        .filter_map(parse_line)
-        .map(overlap)
-        .fold(0, |a, b| a + if b { 1 } else { 0 });
+    const GENERATOR: &'static [OptionSpec] = &[
+        OptionSpec {
+            name: "pairs",
+            kind: OptionKind::Range {
+                min: 1,
+                max: 1_000_000,
+            },
+            defaults: ["1000", "1000"],
+            help: "number of pairs of elves",
+        },
+        OptionSpec {
+            name: "sections",
+            kind: OptionKind::Range {
+                min: 1,
+                max: 1_000_000,
+            },
+            defaults: ["99", "99"],
+            help: "number of sections of the camp",
+        },
+    ];
 
-    println!(
-        "The number of overlapping ranges is {}",
-        number_fully_contained
-    );
+    fn parse_with(input: &str, strictness: Strictness) -> Result<Self::Input> {
+        let mut pairs = Vec::new();
+        for line in parse::lines(input) {
+            match line.parse(pair()) {
+                Ok(pair) => pairs.push(pair),
+                Err(column) => strictness.check(line.error(
+                    Self::DAY,
+                    column,
+                    "`<start>-<end>,<start>-<end>`",
+                ))?,
+            }
+        }
+        Ok(pairs)
+    }
 
-    Ok(())
-}
+    fn generate(rng: &mut Rng, options: &Options) -> Option<String> {
+        let sections = options.integer("sections") as u64;
+        let mut assignment = || {
+            let start = rng.range(1..=sections);
+            format!("{}-{}", start, rng.range(start..=sections))
+        };
+        let pairs = (0..options.integer("pairs"))
+            .map(|_| format!("{},{}\n", assignment(), assignment()))
+            .collect();
+        Some(pairs)
+    }
+
+    fn part1(input: &Self::Input, _options: &Options) -> Answer {
+        let number_fully_contained = input
+            .iter()
+            .copied()
+            .map(either_fully_contained)
+            .enumerate()
+            .map(|(index, contained)| {
+                event!(
+                    Level::Debug,
+                    "pair",
+                    line = index + 1,
+                    contained = contained
+                );
+                contained
+            })
+            .fold(0, |a, b| a + if b { 1 } else { 0 });
+
+        number_fully_contained.into()
+    }
+
+    fn part2(input: &Self::Input, _options: &Options) -> Answer {
+        let number_overlapping = input
+            .iter()
+            .copied()
+            .map(overlap)
+            .enumerate()
+            .map(|(index, overlapping)| {
+                event!(
+                    Level::Debug,
+                    "pair",
+                    line = index + 1,
+                    overlap = overlapping
+                );
+                overlapping
+            })
+            .fold(0, |a, b| a + if b { 1 } else { 0 });
 
-pub fn run(args: &[String]) -> std::io::Result<()> {
-    let file = File::open("input/day04.txt")?;
-    let reader = BufReader::new(file);
+        number_overlapping.into()
+    }
 
-    match args.get(0).map(|s| s.parse::<i32>()) {
-        Some(Ok(1)) => run_part1(reader),
-        Some(Ok(2)) => run_part2(reader),
-        _ => {
-            println!("Unknown part");
-            Ok(())
+    fn describe(part: Part, _options: &Options, answer: &Answer) -> String {
+        match part {
+            Part::One => format!("The number of fully contained ranges is {}", answer),
+            Part::Two => format!("The number of overlapping ranges is {}", answer),
         }
-    }?;
+    }
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::example_tests;
+
+    example_tests! {
+        Day04;
+        example: part1 = 2, part2 = 4;
+    }
 }
@@ -1,119 +1,442 @@
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
-use std::io::Lines;
-use std::iter::*;
+use crate::error::{ParseError, Result, Strictness};
+use crate::generate::Rng;
+use crate::grid::Point;
+use crate::options::{OptionKind, OptionSpec, Options};
+use crate::parse::{self, literal, number, opt, satisfy, separated, Parser, Section};
+use crate::simulation::{self, Simulation};
+use crate::solution::{Answer, Part, Solution};
+use crate::trace::{event, Level};
+use crate::visualize::{self, Cell, Frame, Frames, Rgb};
 
-fn parse_crate_image(lines_of_image: &Vec<String>) -> Vec<Vec<char>> {
-    let height = lines_of_image.len() - 1;
-    let width = ((lines_of_image[0].len()) + 1) / 4;
+/// A crate like `[A]`, or three spaces where there is none.
+fn crate_cell<'a>() -> impl Parser<'a, Option<char>> {
+    literal("[")
+        .then(satisfy(|c| c.is_ascii_uppercase()))
+        .skip(literal("]"))
+        .map(Some)
+        .or(literal("   ").map(|_| None))
+}
 
-    let mut res = Vec::new();
+/// The number below a stack, like ` 1 `. The trailing space may be missing at
+/// the end of the line.
+fn stack_label<'a>() -> impl Parser<'a, usize> {
+    literal(" ").then(number()).skip(opt(literal(" ")))
+}
 
-    for _ in 0..width {
-        res.push(Vec::new());
-    }
+/// Parses the drawing of the stacks into the crates of each stack, bottom first,
+/// and returns every malformed line of it, the labels first.
+fn parse_drawing(drawing: &Section) -> (Vec<Vec<char>>, Vec<ParseError>) {
+    let error = |line: parse::Line, column| {
+        line.error(Day05::DAY, column, "a drawing of the stacks of crates")
+    };
+    let mut errors = Vec::new();
 
-    for i in 0..width {
-        let x = 4 * i + 1;
-        for j in 0..height {
-            let stack = res.get_mut(i).unwrap();
-            let y = height - j - 1;
+    let (labels, rows) = drawing.lines.split_last().expect("sections are not empty");
+    let number_of_stacks = match labels.parse(separated(stack_label(), " ")) {
+        Ok(labels) => Some(labels.len()),
+        Err(column) => {
+            errors.push(error(*labels, column));
+            None
+        }
+    };
 
-            if let Some(Some(c)) = lines_of_image.get(y).map(|line| line.chars().nth(x)) {
-                if c == ' ' {
-                    break;
-                }
-                stack.push(c);
+    let mut stacks = vec![Vec::new(); number_of_stacks.unwrap_or(0)];
+    for row in rows.iter().rev() {
+        let crates = match row.parse(separated(crate_cell(), " ")) {
+            Ok(crates) => crates,
+            Err(column) => {
+                errors.push(error(*row, column));
+                continue;
+            }
+        };
+        match number_of_stacks {
+            Some(number_of_stacks) if crates.len() > number_of_stacks => {
+                errors.push(error(*row, 4 * number_of_stacks + 1));
+                continue;
             }
+            // Without labels the rows tell how many stacks there are.
+            None if crates.len() > stacks.len() => stacks.resize(crates.len(), Vec::new()),
+            _ => (),
+        }
+        for (stack, crate_name) in stacks.iter_mut().zip(crates) {
+            stack.extend(crate_name);
         }
     }
-    res
+    (stacks, errors)
 }
 
-fn get_image(lines: &mut Lines<BufReader<File>>) -> Vec<String> {
-    lines
-        .map_while(Result::ok)
-        .take_while(|x| !x.is_empty())
-        .collect()
+/// A move as `(how_many, from, to)` with zero based stack indices.
+fn crane_move<'a>(number_of_stacks: usize) -> impl Parser<'a, (usize, usize, usize)> {
+    let stack = move || {
+        number::<usize>()
+            .try_map(move |stack| (1..=number_of_stacks).contains(&stack).then(|| stack - 1))
+    };
+    literal("move ")
+        .then(number())
+        .skip(literal(" from "))
+        .and(stack())
+        .skip(literal(" to "))
+        .and(stack())
+        .map(|((how_many, from), to)| (how_many, from, to))
 }
 
-fn get_move(s: String) -> Option<(usize, usize, usize)> {
-    let words: Vec<_> = s.split(' ').collect();
-
-    let how_many = words.get(1).and_then(|s| s.parse::<usize>().ok());
-    let from = words.get(3).and_then(|s| s.parse::<usize>().ok());
-    let to = words.get(5).and_then(|s| s.parse::<usize>().ok());
+pub struct Day05;
 
-    match (how_many, from, to) {
-        (Some(how_many), Some(from), Some(to)) => Some((how_many, from - 1, to - 1)),
-        _ => None,
-    }
+/// The stacks of crates, bottom first, and the `(how_many, from, to)` moves of the crane.
+pub struct Procedure {
+    stacks: Vec<Vec<char>>,
+    moves: Vec<(usize, usize, usize)>,
 }
 
-fn run_part1(reader: BufReader<File>) -> std::io::Result<()> {
-    let mut lines = reader.lines();
-    let image = get_image(&mut lines);
-    let mut stacks = parse_crate_image(&image);
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Supply Stacks";
+
+    type Input = Procedure;
 
-    for movement in lines.map_while(Result::ok).filter_map(get_move) {
-        let (how_many, from, to) = movement;
-        for _ in 0..how_many {
-            if let Some(c) = stacks[from].pop() {
-                stacks[to].push(c);
+    const GENERATOR: &'static [OptionSpec] = &[
+        OptionSpec {
+            name: "stacks",
+            kind: OptionKind::Range { min: 1, max: 9 },
+            defaults: ["9", "9"],
+            help: "number of stacks, at most 9",
+        },
+        OptionSpec {
+            name: "crates",
+            kind: OptionKind::Range {
+                min: 1,
+                max: 10_000,
+            },
+            defaults: ["50", "50"],
+            help: "number of crates",
+        },
+        OptionSpec {
+            name: "moves",
+            kind: OptionKind::Range {
+                min: 0,
+                max: 100_000,
+            },
+            defaults: ["500", "500"],
+            help: "number of moves of the crane",
+        },
+    ];
+
+    fn parse_with(input: &str, strictness: Strictness) -> Result<Self::Input> {
+        let sections = parse::sections(input);
+        let Some((drawing, procedure)) = sections.split_first() else {
+            return Err(missing_drawing(input).into());
+        };
+        let (stacks, errors) = parse_drawing(drawing);
+        if let Some(error) = errors.into_iter().next() {
+            return Err(error.into());
+        }
+
+        let mut moves = Vec::new();
+        for movement in parse_moves(procedure, stacks.len()) {
+            match movement {
+                Ok(movement) => moves.push(movement),
+                Err(error) => strictness.check(error)?,
             }
         }
+
+        Ok(Procedure { stacks, moves })
+    }
+
+    /// Reports every misaligned or malformed line of the drawing and every
+    /// malformed move.
+    fn check(input: &str) -> Vec<ParseError> {
+        let sections = parse::sections(input);
+        let Some((drawing, procedure)) = sections.split_first() else {
+            return vec![missing_drawing(input)];
+        };
+        let (stacks, mut problems) = parse_drawing(drawing);
+        problems.extend(parse_moves(procedure, stacks.len()).filter_map(|movement| movement.err()));
+        problems.sort_by_key(|problem| problem.line);
+        problems
+    }
+
+    /// A drawing and moves that never take more crates than a stack holds.
+    fn generate(rng: &mut Rng, options: &Options) -> Option<String> {
+        let number_of_stacks = options.integer("stacks") as usize;
+        let mut stacks = vec![Vec::new(); number_of_stacks];
+        for _ in 0..options.integer("crates") {
+            let name = (b'A' + rng.below(26) as u8) as char;
+            stacks[rng.below(number_of_stacks)].push(name);
+        }
+
+        let mut input = drawing(&stacks);
+        input.push('\n');
+
+        for _ in 0..options.integer("moves") {
+            let filled: Vec<_> = (0..number_of_stacks)
+                .filter(|&stack| !stacks[stack].is_empty())
+                .collect();
+            let from = *rng.pick(&filled);
+            let to = match number_of_stacks {
+                1 => from,
+                _ => (from + 1 + rng.below(number_of_stacks - 1)) % number_of_stacks,
+            };
+            let how_many = rng.range(1..=stacks[from].len() as u64) as usize;
+            let remaining = stacks[from].len() - how_many;
+            let moved = stacks[from].split_off(remaining);
+            stacks[to].extend(moved);
+            input.push_str(&format!(
+                "move {} from {} to {}\n",
+                how_many,
+                from + 1,
+                to + 1
+            ));
+        }
+        Some(input)
     }
 
-    let res = stacks
+    fn part1(input: &Self::Input, _options: &Options) -> Answer {
+        let mut crane = Crane::new(input, Model::CrateMover9000);
+        simulation::run_to_end(&mut crane);
+        crane.top_crates().into()
+    }
+
+    fn part2(input: &Self::Input, _options: &Options) -> Answer {
+        let mut crane = Crane::new(input, Model::CrateMover9001);
+        simulation::run_to_end(&mut crane);
+        crane.top_crates().into()
+    }
+
+    /// The stacks after each move of the crane.
+    fn visualize<'a>(input: &'a Self::Input, part: Part, _options: &Options) -> Option<Frames<'a>> {
+        let model = match part {
+            Part::One => Model::CrateMover9000,
+            Part::Two => Model::CrateMover9001,
+        };
+        // High enough for all crates on one stack.
+        let height = input.stacks.iter().map(Vec::len).sum();
+        Some(visualize::frames_of(
+            Crane::new(input, model),
+            move |crane: &Crane| crane.frame(height),
+        ))
+    }
+
+    fn describe(_part: Part, _options: &Options, answer: &Answer) -> String {
+        format!("The top crates are {}", answer)
+    }
+}
+
+fn missing_drawing(input: &str) -> ParseError {
+    ParseError::new(
+        Day05::DAY,
+        1,
+        1,
+        input.lines().next().unwrap_or(""),
+        "a drawing of the stacks of crates",
+    )
+}
+
+/// The moves of the sections after the drawing.
+fn parse_moves<'a>(
+    procedure: &'a [Section<'a>],
+    number_of_stacks: usize,
+) -> impl Iterator<Item = std::result::Result<(usize, usize, usize), ParseError>> + 'a {
+    procedure
         .iter()
-        .filter_map(|stack| stack.last())
-        .collect::<String>();
-    println!("The top crates are {}", res);
+        .flat_map(|section| &section.lines)
+        .map(move |line| {
+            line.parse(crane_move(number_of_stacks)).map_err(|column| {
+                line.error(
+                    Day05::DAY,
+                    column,
+                    "`move <count> from <stack> to <stack>` with existing stacks",
+                )
+            })
+        })
+}
 
-    Ok(())
+/// How the crane moves several crates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    /// One crate after the other, reversing their order.
+    CrateMover9000,
+    /// All crates at once, keeping their order.
+    CrateMover9001,
 }
 
-fn run_part2(reader: BufReader<File>) -> std::io::Result<()> {
-    let mut lines = reader.lines();
-    let image = get_image(&mut lines);
-    let mut stacks = parse_crate_image(&image);
-
-    for movement in lines.map_while(Result::ok).filter_map(get_move) {
-        let (how_many, from, to) = movement;
-        let mut tmp = Vec::new();
-        for _ in 0..how_many {
-            if let Some(c) = stacks[from].pop() {
-                tmp.push(c);
+/// The crane rearranging the stacks, one move per step.
+#[derive(Clone)]
+pub struct Crane<'a> {
+    model: Model,
+    stacks: Vec<Vec<char>>,
+    moves: &'a [(usize, usize, usize)],
+    /// Index of the next move.
+    next: usize,
+}
+
+impl<'a> Crane<'a> {
+    pub fn new(procedure: &'a Procedure, model: Model) -> Self {
+        Crane {
+            model,
+            stacks: procedure.stacks.clone(),
+            moves: &procedure.moves,
+            next: 0,
+        }
+    }
+
+    pub fn top_crates(&self) -> String {
+        get_top_crates(&self.stacks)
+    }
+
+    fn next_move(&self) -> String {
+        match self.moves.get(self.next) {
+            Some((how_many, from, to)) => format!(
+                "Move {} of {}: move {} from {} to {}",
+                self.next + 1,
+                self.moves.len(),
+                how_many,
+                from + 1,
+                to + 1
+            ),
+            None => format!("All {} moves made", self.moves.len()),
+        }
+    }
+
+    /// The stacks side by side in a frame `height` crates high, each crate
+    /// colored by its name.
+    fn frame(&self, height: usize) -> Frame {
+        let mut frame = Frame::new(self.stacks.len(), height);
+        for (x, stack) in self.stacks.iter().enumerate() {
+            for (level, &name) in stack.iter().enumerate() {
+                let point = Point::new(x as i32, (height - 1 - level) as i32);
+                let color = Rgb::distinct((name as u8 - b'A') as usize);
+                frame.set(point, Cell::new(color, name));
             }
         }
+        frame.caption = self.next_move();
+        frame
+    }
+}
+
+impl<'a> Simulation for Crane<'a> {
+    type Snapshot = Crane<'a>;
 
-        while let Some(c) = tmp.pop() {
-            stacks[to].push(c);
+    fn step(&mut self) {
+        let Some(&(how_many, from, to)) = self.moves.get(self.next) else {
+            return;
+        };
+        let remaining = self.stacks[from].len().saturating_sub(how_many);
+        let mut moved = self.stacks[from].split_off(remaining);
+        if self.model == Model::CrateMover9000 {
+            moved.reverse();
         }
+        event!(
+            Level::Debug,
+            "move",
+            crates = moved.iter().collect::<String>(),
+            from = from + 1,
+            to = to + 1
+        );
+        self.stacks[to].extend(moved);
+        self.next += 1;
+    }
+
+    fn is_done(&self) -> bool {
+        self.next == self.moves.len()
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.clone()
     }
 
-    let res = stacks
+    fn restore(&mut self, snapshot: &Self::Snapshot) {
+        self.clone_from(snapshot);
+    }
+
+    /// The stacks drawn like in the input, and the next move.
+    fn dump(&self) -> String {
+        format!("{}\n{}\n", drawing(&self.stacks), self.next_move())
+    }
+}
+
+/// Draws the stacks like the puzzle input does, with a line per level of
+/// crates and the labels of the stacks below.
+fn drawing(stacks: &[Vec<char>]) -> String {
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut drawing = String::new();
+    for level in (0..height).rev() {
+        let cells: Vec<_> = stacks
+            .iter()
+            .map(|stack| match stack.get(level) {
+                Some(name) => format!("[{}]", name),
+                None => "   ".into(),
+            })
+            .collect();
+        drawing.push_str(&cells.join(" "));
+        drawing.push('\n');
+    }
+    let labels: Vec<_> = (1..=stacks.len())
+        .map(|stack| format!(" {} ", stack))
+        .collect();
+    drawing.push_str(&labels.join(" "));
+    drawing.push('\n');
+    drawing
+}
+
+fn get_top_crates(stacks: &[Vec<char>]) -> String {
+    stacks
         .iter()
         .filter_map(|stack| stack.last())
-        .collect::<String>();
-    println!("The top crates are {}", res);
-
-    Ok(())
+        .collect::<String>()
 }
 
-pub fn run(args: &[String]) -> std::io::Result<()> {
-    let file = File::open("input/day05.txt")?;
-    let reader = BufReader::new(file);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{example, example_tests};
 
-    match args.get(0).map(|s| s.parse::<i32>()) {
-        Some(Ok(1)) => run_part1(reader),
-        Some(Ok(2)) => run_part2(reader),
-        _ => {
-            println!("Unknown part");
-            Ok(())
-        }
-    }?;
+    example_tests! {
+        Day05;
+        example: part1 = "CMZ", part2 = "MCD";
+    }
+
+    #[test]
+    fn crane_should_dump_the_stacks_and_next_move() {
+        let procedure = example::<Day05>("example");
+        let mut crane = Crane::new(&procedure, Model::CrateMover9000);
+        crane.step();
+
+        assert_eq!(
+            concat!(
+                "[D]        \n",
+                "[N] [C]    \n",
+                "[Z] [M] [P]\n",
+                " 1   2   3 \n",
+                "\n",
+                "Move 2 of 4: move 3 from 1 to 3\n",
+            ),
+            crane.dump()
+        );
+    }
+
+    #[test]
+    fn check_should_report_every_problem() {
+        let input = concat!(
+            "    [D]    \n",
+            "[N]  [C]   \n",
+            "[Z] [M] [P]\n",
+            " 1   2   3 \n",
+            "\n",
+            "move 1 from 2 to 1\n",
+            "move 3 from 1 to 4\n",
+            "move two from 2 to 1\n",
+        );
 
-    Ok(())
+        let problems = Day05::check(input);
+
+        let positions: Vec<_> = problems
+            .iter()
+            .map(|problem| (problem.line, problem.column))
+            .collect();
+        assert_eq!(vec![(2, 6), (7, 18), (8, 6)], positions);
+        assert_eq!(
+            2,
+            Day05::check(&input.replace("[N]  [C] ", "[N] [C]  ")).len()
+        );
+    }
 }
@@ -1,32 +1,108 @@
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::Read;
 
-pub fn run(args: &[String]) -> std::io::Result<()> {
-    let mut file = File::open("input/day06.txt")?;
-    let mut input = vec![];
-    file.read_to_end(&mut input)?;
+use crate::error::{Result, Strictness};
+use crate::generate::Rng;
+use crate::options::{OptionKind, OptionSpec, Options};
+use crate::parse::{self, take_while1, Line};
+use crate::solution::{Answer, Part, Solution};
+use crate::trace::{event, Level};
 
-    match args.get(0).map(|s| s.parse::<i32>()) {
-        Some(Ok(1)) => find_start_of_distinct(&input, 4),
-        Some(Ok(2)) => find_start_of_distinct(&input, 14),
-        _ => panic!("Unknown part"),
-    }?;
+pub struct Day06;
 
-    Ok(())
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Tuning Trouble";
+
+    /// The datastream buffer.
+    type Input = Vec<u8>;
+
+    const GENERATOR: &'static [OptionSpec] = &[OptionSpec {
+        name: "length",
+        kind: OptionKind::Range {
+            min: 14,
+            max: 10_000_000,
+        },
+        defaults: ["4096", "4096"],
+        help: "number of characters of the datastream",
+    }];
+
+    fn parse_with(input: &str, strictness: Strictness) -> Result<Self::Input> {
+        let mut lines = parse::lines(input);
+        let datastream = lines.next().unwrap_or(Line {
+            number: 1,
+            text: "",
+        });
+        let column = match lines.next() {
+            Some(line) => Some((line, 1)),
+            None => datastream
+                .parse(take_while1(|c| c.is_ascii_lowercase()))
+                .err()
+                .map(|column| (datastream, column)),
+        };
+        if let Some((line, column)) = column {
+            strictness.check(line.error(Self::DAY, column, "a single line of characters a-z"))?;
+        }
+
+        Ok(datastream.text.as_bytes().to_vec())
+    }
+
+    /// A datastream whose last 14 characters are different from each other, so
+    /// that both markers exist.
+    fn generate(rng: &mut Rng, options: &Options) -> Option<String> {
+        let length = options.integer("length") as usize;
+        let mut datastream: Vec<u8> = (0..length - 14)
+            .map(|_| b'a' + rng.below(26) as u8)
+            .collect();
+        let mut marker: Vec<u8> = (b'a'..=b'z').collect();
+        rng.shuffle(&mut marker);
+        datastream.extend(&marker[..14]);
+        datastream.push(b'\n');
+        Some(String::from_utf8(datastream).expect("letters are ASCII"))
+    }
+
+    fn part1(input: &Self::Input, _options: &Options) -> Answer {
+        find_start_of_distinct(input, 4).into()
+    }
+
+    fn part2(input: &Self::Input, _options: &Options) -> Answer {
+        find_start_of_distinct(input, 14).into()
+    }
+
+    fn describe(part: Part, _options: &Options, answer: &Answer) -> String {
+        let nr_of_distinct = match part {
+            Part::One => 4,
+            Part::Two => 14,
+        };
+        format!(
+            "First {} different characters start at index {}",
+            nr_of_distinct, answer
+        )
+    }
 }
 
-fn find_start_of_distinct(input: &[u8], nr_of_distinct: usize) -> std::io::Result<()> {
-    if let Some((index, _)) = input
+fn find_start_of_distinct(input: &[u8], nr_of_distinct: usize) -> Option<usize> {
+    input
         .windows(nr_of_distinct)
         .enumerate()
         .find(|(_, bytes)| (*bytes).iter().copied().collect::<HashSet<_>>().len() == nr_of_distinct)
-    {
-        println!(
-            "First {} different characters start at index {}",
-            nr_of_distinct,
+        .map(|(index, bytes)| {
+            event!(
+                Level::Info,
+                "marker",
+                marker = String::from_utf8_lossy(bytes),
+                end = index + nr_of_distinct
+            );
             index + nr_of_distinct
-        );
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::example_tests;
+
+    example_tests! {
+        Day06;
+        example: part1 = 7, part2 = 19;
     }
-    Ok(())
 }
@@ -1,7 +1,11 @@
-use std::collections::HashMap;
-use std::io::prelude::*;
-use std::io::BufReader;
-use std::io::Lines;
+use std::collections::{HashMap, HashSet};
+
+use crate::error::{Result, Strictness};
+use crate::generate::Rng;
+use crate::options::{OptionKind, OptionSpec, Options};
+use crate::parse::{self, literal, number, take_while1, Parser};
+use crate::solution::{Answer, Part, Solution};
+use crate::trace::{event, Level};
 
 enum Output {
     ChangeDirectory(String),
@@ -16,7 +20,7 @@ struct File {
 }
 
 #[derive(Debug)]
-struct Directory {
+pub struct Directory {
     sub_directories: HashMap<String, Directory>,
     files: HashMap<String, usize>,
 }
@@ -44,107 +48,250 @@ impl Directory {
     }
 }
 
-impl TryFrom<String> for Output {
-    type Error = ();
-
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        if value.starts_with("$ cd ") {
-            Ok(Output::ChangeDirectory(
-                value.chars().skip("$ cd ".len()).collect(),
-            ))
-        } else if value.starts_with("$ ls") {
-            Ok(Output::List)
-        } else if value.starts_with("dir ") {
-            Ok(Output::Directory(
-                value.chars().skip("dir ".len()).collect(),
-            ))
-        } else if let Some((Some(size), file_name)) = value
-            .split_once(' ')
-            .map(|(s, file_name)| (s.parse::<usize>().ok(), String::from(file_name)))
-        {
-            Ok(Output::File(size, file_name))
-        } else {
-            Err(())
+impl TryFrom<&str> for Output {
+    /// The column where the line stops matching.
+    type Error = usize;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        let name = || take_while1(|_| true).map(String::from);
+        let output = literal("$ cd ")
+            .then(name())
+            .map(Output::ChangeDirectory)
+            .or(literal("$ ls").map(|_| Output::List))
+            .or(literal("dir ").then(name()).map(Output::Directory))
+            .or(number()
+                .skip(literal(" "))
+                .and(name())
+                .map(|(size, file_name)| Output::File(size, file_name)));
+
+        parse::parse_all(value, output)
+    }
+}
+
+/// Lists a random directory into `transcript` and descends into its
+/// subdirectories while `depth` is not used up.
+fn write_directory(
+    rng: &mut Rng,
+    transcript: &mut String,
+    depth: usize,
+    branching: u64,
+    files: u64,
+) {
+    let mut names = HashSet::new();
+    let mut unique_name = |rng: &mut Rng| loop {
+        let name: String = (0..rng.range(1..=8))
+            .map(|_| (b'a' + rng.below(26) as u8) as char)
+            .collect();
+        if names.insert(name.clone()) {
+            return name;
+        }
+    };
+
+    let directories: Vec<_> = match depth {
+        0 => Vec::new(),
+        _ => (0..rng.range(0..=branching))
+            .map(|_| unique_name(rng))
+            .collect(),
+    };
+    let mut entries: Vec<_> = directories
+        .iter()
+        .map(|name| format!("dir {}", name))
+        .collect();
+    for _ in 0..rng.range(0..=files) {
+        let mut name = unique_name(rng);
+        if rng.chance(0.5) {
+            name = format!(
+                "{}.{}",
+                name,
+                rng.pick(&["txt", "dat", "log", "lst", "ext"])
+            );
         }
+        entries.push(format!("{} {}", rng.range(1..=300_000), name));
+    }
+    rng.shuffle(&mut entries);
+
+    transcript.push_str("$ ls\n");
+    for entry in entries {
+        transcript.push_str(&entry);
+        transcript.push('\n');
+    }
+    for name in directories {
+        transcript.push_str(&format!("$ cd {}\n", name));
+        write_directory(rng, transcript, depth - 1, branching, files);
+        transcript.push_str("$ cd ..\n");
     }
 }
 
-pub fn run(_args: &[String]) -> std::io::Result<()> {
-    let file = std::fs::File::open("input/day07.txt")?;
-    let reader = BufReader::new(file);
+pub struct Day07;
 
-    let root = get_directory_tree(&mut reader.lines());
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "No Space Left On Device";
 
-    let all_directories = root.get_sub_directories("");
+    /// The root of the directory tree explored in the terminal output.
+    type Input = Directory;
 
-    let sizes_of_directories: Vec<usize> = all_directories
-        .values()
-        .map(|dir| dir.get_inclusive_size())
-        .collect();
+    const OPTIONS: &'static [OptionSpec] = &[
+        OptionSpec {
+            name: "max-size",
+            kind: OptionKind::Integer { min: 0 },
+            defaults: ["100000", "100000"],
+            help: "part 1 adds up the directories smaller than this",
+        },
+        OptionSpec {
+            name: "disk-size",
+            kind: OptionKind::Integer { min: 0 },
+            defaults: ["70000000", "70000000"],
+            help: "total size of the disk",
+        },
+        OptionSpec {
+            name: "free-space",
+            kind: OptionKind::Integer { min: 0 },
+            defaults: ["30000000", "30000000"],
+            help: "free space part 2 needs on the disk",
+        },
+    ];
 
-    let max_size_for_part_1 = 100_000;
-    let sum_of_inclusive_sizes: usize = sizes_of_directories
-        .iter()
-        .filter(|size| **size < max_size_for_part_1)
-        .sum();
-    println!(
-        "The sum of the inclusive sizes less than {} is {}",
-        max_size_for_part_1, sum_of_inclusive_sizes
-    );
-
-    let total_size: usize = 70000000;
-    let needed_free_space: usize = 30000000;
-    let total_used_size = root.get_inclusive_size();
-    let needed_to_delete = total_used_size - (total_size - needed_free_space);
-
-    let min_feasible_size = sizes_of_directories
-        .iter()
-        .filter(|size| **size >= needed_to_delete)
-        .min()
-        .unwrap();
+    const GENERATOR: &'static [OptionSpec] = &[
+        OptionSpec {
+            name: "depth",
+            kind: OptionKind::Range { min: 0, max: 6 },
+            defaults: ["4", "4"],
+            help: "deepest level of directories below the root",
+        },
+        OptionSpec {
+            name: "branching",
+            kind: OptionKind::Range { min: 0, max: 10 },
+            defaults: ["4", "4"],
+            help: "most directories inside one directory",
+        },
+        OptionSpec {
+            name: "files",
+            kind: OptionKind::Range { min: 0, max: 100 },
+            defaults: ["5", "5"],
+            help: "most files inside one directory",
+        },
+    ];
+
+    fn parse_with(input: &str, strictness: Strictness) -> Result<Self::Input> {
+        get_directory_tree(input, strictness)
+    }
+
+    /// The terminal output of listing every directory once, depth first.
+    fn generate(rng: &mut Rng, options: &Options) -> Option<String> {
+        let mut transcript = String::from("$ cd /\n");
+        write_directory(
+            rng,
+            &mut transcript,
+            options.integer("depth") as usize,
+            options.integer("branching") as u64,
+            options.integer("files") as u64,
+        );
+        Some(transcript)
+    }
+
+    fn part1(root: &Self::Input, options: &Options) -> Answer {
+        let max_size_for_part_1 = options.integer("max-size") as usize;
+        let sum_of_inclusive_sizes: usize = get_sizes_of_directories(root)
+            .into_iter()
+            .filter(|size| *size < max_size_for_part_1)
+            .sum();
+
+        sum_of_inclusive_sizes.into()
+    }
+
+    fn part2(root: &Self::Input, options: &Options) -> Answer {
+        let total_size = options.integer("disk-size") as usize;
+        let needed_free_space = options.integer("free-space") as usize;
+        let total_used_size = root.get_inclusive_size();
+        let needed_to_delete =
+            total_used_size.saturating_sub(total_size.saturating_sub(needed_free_space));
+        event!(
+            Level::Info,
+            "space",
+            used = total_used_size,
+            to_delete = needed_to_delete
+        );
 
-    println!(
-        "The smallest directory size over {} is {}",
-        needed_to_delete, min_feasible_size
-    );
+        get_sizes_of_directories(root)
+            .into_iter()
+            .filter(|size| *size >= needed_to_delete)
+            .min()
+            .into()
+    }
+
+    fn describe(part: Part, options: &Options, answer: &Answer) -> String {
+        match part {
+            Part::One => format!(
+                "The sum of the inclusive sizes less than {} is {}",
+                options.integer("max-size"),
+                answer
+            ),
+            Part::Two => format!("The smallest directory size to delete is {}", answer),
+        }
+    }
+}
 
-    Ok(())
+fn get_sizes_of_directories(root: &Directory) -> Vec<usize> {
+    root.get_sub_directories("")
+        .values()
+        .map(|dir| dir.get_inclusive_size())
+        .collect()
 }
 
-fn get_directory_tree(lines: &mut Lines<BufReader<std::fs::File>>) -> Directory {
+/// Replays the terminal output. In lenient mode it stops at the first line that
+/// is not understood.
+fn get_directory_tree(input: &str, strictness: Strictness) -> Result<Directory> {
     let mut current_path: Vec<String> = vec![];
     let mut root = Directory {
         files: HashMap::new(),
         sub_directories: HashMap::new(),
     };
 
-    while let Some(output) = lines
-        .next()
-        .and_then(Result::ok)
-        .map(Output::try_from)
-        .and_then(Result::ok)
-    {
+    for line in parse::lines(input) {
+        let output = match Output::try_from(line.text) {
+            Ok(output) => output,
+            Err(column) => {
+                strictness.check(line.error(
+                    Day07::DAY,
+                    column,
+                    "`$ cd <dir>`, `$ ls`, `dir <name>` or `<size> <name>`",
+                ))?;
+                break;
+            }
+        };
+
         match output {
-            Output::ChangeDirectory(relative_path) => match relative_path.as_str() {
-                "/" => current_path.clear(),
-                ".." => _ = current_path.pop(),
-                _ => {
-                    current_path.push(relative_path);
-                    ensure_directory_exists(&mut root, &current_path);
+            Output::ChangeDirectory(relative_path) => {
+                event!(Level::Debug, "cd", dir = relative_path);
+                match relative_path.as_str() {
+                    "/" => current_path.clear(),
+                    ".." => _ = current_path.pop(),
+                    _ => {
+                        current_path.push(relative_path);
+                        ensure_directory_exists(&mut root, &current_path);
+                    }
                 }
-            },
-            Output::List => (),
+            }
+            Output::List => event!(Level::Debug, "ls", path = display_path(&current_path)),
             Output::File(size, file_name) => {
+                event!(Level::Trace, "file", name = file_name, size = size);
                 add_file(&mut root, &current_path, File { size, file_name });
             }
             Output::Directory(name) => {
+                event!(Level::Trace, "dir", name = name);
                 current_path.push(name);
                 ensure_directory_exists(&mut root, &current_path);
                 current_path.pop();
             }
         };
     }
-    root
+    Ok(root)
+}
+
+/// The path as the terminal shows it, `/` for the root.
+fn display_path(path: &[String]) -> String {
+    format!("/{}", path.join("/"))
 }
 
 fn add_file(root: &mut Directory, current_path: &[String], file: File) {
@@ -183,3 +330,14 @@ fn ensure_directory_exists(root: &mut Directory, current_path: &[String]) {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::example_tests;
+
+    example_tests! {
+        Day07;
+        example: part1 = 95437, part2 = 24933642;
+    }
+}
@@ -1,146 +1,235 @@
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
+use crate::error::{ParseError, Result, Strictness};
+use crate::generate::Rng;
+use crate::grid::{Direction, Grid, Point};
+use crate::options::{OptionKind, OptionSpec, Options};
+use crate::parse;
+use crate::solution::{Answer, Part, Solution};
+use crate::trace::{event, Level};
+use crate::visualize::{Cell, Frame, Frames, Rgb};
+
+pub struct Day08;
+
+const EXPECTED: &str = "a rectangle of tree heights 0-9";
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Treetop Tree House";
+
+    /// The height of each tree.
+    type Input = Grid<u8>;
+
+    const GENERATOR: &'static [OptionSpec] = &[
+        OptionSpec {
+            name: "width",
+            kind: OptionKind::Range { min: 1, max: 1000 },
+            defaults: ["99", "99"],
+            help: "number of trees in a row",
+        },
+        OptionSpec {
+            name: "height",
+            kind: OptionKind::Range { min: 1, max: 1000 },
+            defaults: ["99", "99"],
+            help: "number of rows of trees",
+        },
+    ];
+
+    fn parse_with(input: &str, _strictness: Strictness) -> Result<Self::Input> {
+        Grid::parse(input, |c| c.to_digit(10).map(|height| height as u8)).map_err(
+            |(line, column)| {
+                let text = input.lines().nth(line - 1).unwrap_or_default();
+                ParseError::new(Self::DAY, line, column, text, EXPECTED).into()
+            },
+        )
+    }
 
-pub fn run(args: &[String]) -> std::io::Result<()> {
-    let tree_heights = read_tree_heights_from_file("input/day08.txt")?;
+    /// Reports every character that is not a digit and every line that is
+    /// shorter or longer than the first one.
+    fn check(input: &str) -> Vec<ParseError> {
+        let mut problems = Vec::new();
+        let width = input.lines().next().map_or(0, |line| line.chars().count());
+        if width == 0 {
+            problems.push(ParseError::new(Self::DAY, 1, 1, "", EXPECTED));
+        }
+        for line in parse::lines(input) {
+            problems.extend(
+                line.text
+                    .chars()
+                    .enumerate()
+                    .filter(|(_, c)| !c.is_ascii_digit())
+                    .map(|(index, _)| line.error(Self::DAY, index + 1, EXPECTED)),
+            );
+            let length = line.text.chars().count();
+            if length != width && width > 0 {
+                problems.push(line.error(Self::DAY, length.min(width) + 1, EXPECTED));
+            }
+        }
+        problems
+    }
 
-    match args.get(0).and_then(|s| s.parse::<i32>().ok()) {
-        Some(1i32) => part1(tree_heights),
-        Some(2i32) => part2(tree_heights),
-        _ => panic!("Unknown part"),
+    fn generate(rng: &mut Rng, options: &Options) -> Option<String> {
+        let width = options.integer("width") as usize;
+        let forest = Grid::new(width, options.integer("height") as usize, ());
+        let forest = forest.map(|_| (b'0' + rng.below(10) as u8) as char);
+        Some(forest.render(|&height| height))
     }
 
-    Ok(())
-}
+    fn part1(input: &Self::Input, _options: &Options) -> Answer {
+        part1(input).into()
+    }
 
-fn part1(tree_heights: Vec<Vec<u8>>) {
-    let mut tree_hights_and_visibility: Vec<Vec<_>> = tree_heights
-        .iter()
-        .map(|line| line.iter().copied().map(|height| (height, false)).collect())
-        .collect();
-
-    let height = tree_hights_and_visibility.len();
-    let width = tree_hights_and_visibility[0].len();
-
-    for line in &mut tree_hights_and_visibility {
-        // Left to right
-        let mut highest: Option<u8> = None;
-        for (height, is_visible) in line.iter_mut() {
-            if highest.is_none() || highest.unwrap() < *height {
-                highest = Some(*height);
-                *is_visible = true;
-            }
-        }
+    fn part2(input: &Self::Input, _options: &Options) -> Answer {
+        part2(input).into()
+    }
 
-        // Right to left
-        highest = None;
-        for j in (0..width).rev() {
-            let (height, is_visible) = &mut line[j];
-            if highest.is_none() || highest.unwrap() < *height {
-                highest = Some(*height);
-                *is_visible = true;
+    /// The forest revealed row by row, with the visible trees lit in part 1 and
+    /// the trees colored by their scenic score in part 2.
+    fn visualize<'a>(input: &'a Self::Input, part: Part, _options: &Options) -> Option<Frames<'a>> {
+        let scores = match part {
+            Part::One => visibility(input).map(|&visible| visible as usize),
+            Part::Two => {
+                let mut scores = Grid::new(input.width(), input.height(), 0);
+                for tree in input.points() {
+                    scores[tree] = scenic_score(tree, input);
+                }
+                scores
             }
-        }
+        };
+        let best = scores.iter().copied().max().unwrap_or(0).max(1);
+
+        Some(Box::new((1..=input.height()).map(move |rows| {
+            let mut frame = Frame::new(input.width(), input.height());
+            for tree in input.points().take(rows * input.width()) {
+                let height = input[tree];
+                let shade = Rgb(20, 60, 20).mix(Rgb(90, 150, 70), height as f64 / 9.0);
+                let highlight = match part {
+                    Part::One => Rgb(255, 225, 25),
+                    Part::Two => Rgb(230, 25, 75),
+                };
+                let color = shade.mix(highlight, scores[tree] as f64 / best as f64);
+                let glyph = char::from_digit(height.into(), 10).unwrap_or('?');
+                frame.set(tree, Cell::new(color, glyph));
+            }
+            let revealed = scores.points().take(rows * input.width());
+            frame.caption = match part {
+                Part::One => format!(
+                    "Row {} of {}: {} trees visible",
+                    rows,
+                    input.height(),
+                    revealed.filter(|&tree| scores[tree] > 0).count()
+                ),
+                Part::Two => format!(
+                    "Row {} of {}: best scenic score {}",
+                    rows,
+                    input.height(),
+                    revealed.map(|tree| scores[tree]).max().unwrap_or(0)
+                ),
+            };
+            frame
+        })))
     }
 
-    for j in 0..width {
-        // Top to bottom
-        let mut highest: Option<u8> = None;
-        for (height, is_visible) in &mut tree_hights_and_visibility[j] {
-            if highest.is_none() || highest.unwrap() < *height {
-                highest = Some(*height);
-                *is_visible = true;
-            }
+    fn describe(part: Part, _options: &Options, answer: &Answer) -> String {
+        match part {
+            Part::One => format!("There are {} trees visible", answer),
+            Part::Two => format!("The maximum scenic score is {}", answer),
         }
+    }
+}
 
-        // Bottom to top
-        let mut highest: Option<u8> = None;
-        for i in (0..height).rev() {
-            let (height, is_visible) = &mut tree_hights_and_visibility[i][j];
-            if highest.is_none() || highest.unwrap() < *height {
-                highest = Some(*height);
-                *is_visible = true;
+fn part1(tree_heights: &Grid<u8>) -> usize {
+    let visible = visibility(tree_heights);
+    visible
+        .points()
+        .filter(|&tree| {
+            event!(Level::Trace, "tree", tree = tree, visible = visible[tree]);
+            visible[tree]
+        })
+        .count()
+}
+
+/// Which trees are visible from outside the forest, i.e. have only lower trees
+/// between them and an edge. Walks every row and column once from each end,
+/// keeping the highest tree seen so far.
+fn visibility(tree_heights: &Grid<u8>) -> Grid<bool> {
+    let (width, height) = (tree_heights.width() as i32, tree_heights.height() as i32);
+    let rows = (0..height).flat_map(|y| {
+        [
+            (Point::new(-1, y), Direction::Right),
+            (Point::new(width, y), Direction::Left),
+        ]
+    });
+    let columns = (0..width).flat_map(|x| {
+        [
+            (Point::new(x, -1), Direction::Down),
+            (Point::new(x, height), Direction::Up),
+        ]
+    });
+
+    let mut visible = Grid::new(tree_heights.width(), tree_heights.height(), false);
+    for (outside, direction) in rows.chain(columns) {
+        let mut highest = None;
+        for tree in tree_heights.ray(outside, direction) {
+            if highest < Some(tree_heights[tree]) {
+                highest = Some(tree_heights[tree]);
+                visible[tree] = true;
             }
         }
     }
-
-    let nr_of_visible_trees =
-        tree_hights_and_visibility
-            .iter()
-            .fold(0usize, |nr_of_visible_trees, line| {
-                nr_of_visible_trees
-                    + line
-                        .iter()
-                        .map(|(_, is_visible)| *is_visible as usize)
-                        .sum::<usize>()
-            });
-
-    println!("There are {} trees visible", nr_of_visible_trees);
+    visible
 }
 
-fn part2(tree_heights: Vec<Vec<u8>>) {
-    let maximum_scenic_score = tree_heights
-        .iter()
-        .enumerate()
-        .map(|(i, line)| {
-            line.iter()
-                .enumerate()
-                .map(|(j, _)| scenic_score(i, j, &tree_heights))
-                .max()
-                .unwrap()
-        })
-        .max()
+fn part2(tree_heights: &Grid<u8>) -> usize {
+    let (tree, score) = tree_heights
+        .points()
+        .map(|tree| (tree, scenic_score(tree, tree_heights)))
+        .max_by_key(|&(_, score)| score)
         .unwrap();
-
-    println!("The maximum scenic score is {}", maximum_scenic_score);
+    event!(Level::Info, "best", tree = tree, score = score);
+    score
 }
 
-fn scenic_score(i: usize, j: usize, tree_heights: &Vec<Vec<u8>>) -> usize {
-    let height = tree_heights.len();
-    let width = tree_heights[0].len();
+fn scenic_score(tree: Point, tree_heights: &Grid<u8>) -> usize {
+    Direction::ALL
+        .into_iter()
+        .map(|direction| {
+            let mut viewing_distance = 0;
+            for other in tree_heights.ray(tree, direction) {
+                viewing_distance += 1;
+                if tree_heights[other] >= tree_heights[tree] {
+                    break;
+                }
+            }
+            viewing_distance
+        })
+        .product()
+}
 
-    let mut up: usize = 0;
-    for ii in (0..i).rev() {
-        up += 1;
-        if tree_heights[ii][j] >= tree_heights[i][j] {
-            break;
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::example_tests;
 
-    let mut down: usize = 0;
-    for ii in (i + 1)..height {
-        down += 1;
-        if tree_heights[ii][j] >= tree_heights[i][j] {
-            break;
-        }
+    example_tests! {
+        Day08;
+        example: part1 = 21, part2 = 8;
     }
 
-    let mut left: usize = 0;
-    for jj in (0..j).rev() {
-        left += 1;
-        if tree_heights[i][jj] >= tree_heights[i][j] {
-            break;
-        }
-    }
+    #[test]
+    fn part1_should_see_trees_from_the_top() {
+        // The middle tree is hidden from every side but the top.
+        let tree_heights = Day08::parse("000\n515\n555\n").unwrap();
 
-    let mut right: usize = 0;
-    for jj in (j + 1)..width {
-        right += 1;
-        if tree_heights[i][jj] >= tree_heights[i][j] {
-            break;
-        }
+        assert_eq!(9, part1(&tree_heights));
     }
 
-    up * down * left * right
-}
+    #[test]
+    fn check_should_report_every_problem() {
+        let problems = Day08::check("30373\n2x512\n6533\n335490\n3a3b0\n");
 
-fn read_tree_heights_from_file(path: &str) -> std::io::Result<Vec<Vec<u8>>> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let mut tree_hights_and_visibility: Vec<Vec<u8>> = Vec::new();
-    for line in reader.lines() {
-        tree_hights_and_visibility.push(line?.as_bytes().to_vec());
+        let positions: Vec<_> = problems
+            .iter()
+            .map(|problem| (problem.line, problem.column))
+            .collect();
+        assert_eq!(vec![(2, 2), (3, 5), (4, 6), (5, 2), (5, 4)], positions);
     }
-    Ok(tree_hights_and_visibility)
 }
@@ -1,42 +1,29 @@
-use std::cmp::max;
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
-use std::io::Error;
-use std::io::ErrorKind;
-
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
 
-struct Movement {
+use crate::error::{Result, Strictness};
+use crate::generate::Rng;
+use crate::grid::{Direction, Point};
+use crate::options::{OptionKind, OptionSpec, Options};
+use crate::parse::{self, literal, number, one_of, Parser};
+use crate::simulation::Simulation;
+use crate::solution::{Answer, Part, Solution};
+use crate::trace::{event, Level};
+use crate::visualize::{self, Cell, Frame, Frames, Rgb};
+
+pub struct Movement {
     direction: Direction,
     count: usize,
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy)]
-struct Position {
-    x: i32,
-    y: i32,
-}
-
+#[derive(Clone)]
 struct Rope {
-    position: Position,
+    position: Point,
     rest: Option<Box<Rope>>,
 }
 
 impl Rope {
-    fn make_step(&mut self, direction: &Direction) {
-        match direction {
-            Direction::Up => self.position.x -= 1,
-            Direction::Down => self.position.x += 1,
-            Direction::Left => self.position.y -= 1,
-            Direction::Right => self.position.y += 1,
-        };
+    fn make_step(&mut self, direction: Direction) {
+        self.position = self.position.step(direction);
 
         let mut head = &self.position;
         let mut rope = &mut self.rest;
@@ -44,7 +31,7 @@ impl Rope {
         while rope.is_some() {
             let tmp_rope = rope.as_mut().unwrap();
 
-            let rope_moved = tmp_rope.position.move_towards(head);
+            let rope_moved = move_towards(&mut tmp_rope.position, head);
             if !rope_moved {
                 return;
             }
@@ -54,7 +41,7 @@ impl Rope {
         }
     }
 
-    fn get_tail(&self) -> Position {
+    fn get_tail(&self) -> Point {
         let mut rope = self;
         while let Some(rest) = &rope.rest {
             rope = rest;
@@ -63,81 +50,325 @@ impl Rope {
     }
 }
 
-impl Position {
-    fn move_towards(&mut self, position: &Position) -> bool {
-        let dx = position.x - self.x;
-        let dy = position.y - self.y;
-
-        match max(dx.abs(), dy.abs()) {
-            0 | 1 => false,
-            _ => {
-                self.x += dx.signum();
-                self.y += dy.signum();
-                true
+/// Moves a knot one step towards `head` unless they touch.
+fn move_towards(knot: &mut Point, head: &Point) -> bool {
+    match knot.chebyshev_distance(*head) {
+        0 | 1 => false,
+        _ => {
+            *knot = *knot + (*head - *knot).signum();
+            true
+        }
+    }
+}
+
+impl TryFrom<&str> for Movement {
+    /// The column where the line stops matching.
+    type Error = usize;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        let direction = one_of(&[
+            ("U", Direction::Up),
+            ("D", Direction::Down),
+            ("L", Direction::Left),
+            ("R", Direction::Right),
+        ]);
+        let movement = direction
+            .skip(literal(" "))
+            .and(number())
+            .map(|(direction, count)| Movement { direction, count });
+
+        parse::parse_all(value, movement)
+    }
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+    const TITLE: &'static str = "Rope Bridge";
+
+    /// The motions of the head of the rope.
+    type Input = Vec<Movement>;
+
+    const OPTIONS: &'static [OptionSpec] = &[OptionSpec {
+        name: "knots",
+        kind: OptionKind::Range { min: 1, max: 1000 },
+        defaults: ["2", "10"],
+        help: "number of knots of the rope, including head and tail",
+    }];
+
+    const GENERATOR: &'static [OptionSpec] = &[
+        OptionSpec {
+            name: "motions",
+            kind: OptionKind::Range {
+                min: 1,
+                max: 100_000,
+            },
+            defaults: ["2000", "2000"],
+            help: "number of motions of the head",
+        },
+        OptionSpec {
+            name: "steps",
+            kind: OptionKind::Range { min: 1, max: 100 },
+            defaults: ["20", "20"],
+            help: "most steps of one motion",
+        },
+    ];
+
+    fn parse_with(input: &str, strictness: Strictness) -> Result<Self::Input> {
+        let mut movements = Vec::new();
+        for line in parse::lines(input) {
+            match Movement::try_from(line.text) {
+                Ok(movement) => movements.push(movement),
+                Err(column) => {
+                    strictness.check(line.error(Self::DAY, column, "`<U|D|L|R> <steps>`"))?
+                }
             }
         }
+        Ok(movements)
+    }
+
+    fn generate(rng: &mut Rng, options: &Options) -> Option<String> {
+        let steps = options.integer("steps") as u64;
+        let motions = (0..options.integer("motions"))
+            .map(|_| {
+                format!(
+                    "{} {}\n",
+                    rng.pick(&['U', 'D', 'L', 'R']),
+                    rng.range(1..=steps)
+                )
+            })
+            .collect();
+        Some(motions)
+    }
+
+    fn part1(input: &Self::Input, options: &Options) -> Answer {
+        count_tail_positions(input, options.integer("knots") as usize).into()
+    }
+
+    fn part2(input: &Self::Input, options: &Options) -> Answer {
+        count_tail_positions(input, options.integer("knots") as usize).into()
+    }
+
+    /// The knots and the positions the tail visited after each step of the head.
+    fn visualize<'a>(input: &'a Self::Input, _part: Part, options: &Options) -> Option<Frames<'a>> {
+        let bridge = RopeBridge::new(input, options.integer("knots") as usize);
+        let (min, max) = head_bounds(input);
+        let mut visited = HashSet::new();
+        Some(visualize::frames_of(bridge, move |bridge: &RopeBridge| {
+            visited.insert(bridge.tail());
+            bridge.frame(min, max, &visited)
+        }))
+    }
+
+    fn describe(_part: Part, _options: &Options, answer: &Answer) -> String {
+        format!("Tail was at {} positions", answer)
     }
 }
 
-impl TryFrom<String> for Movement {
-    type Error = std::io::Error;
-
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        match value.split_once(' ') {
-            Some((symbol, number)) => {
-                let direction = match symbol {
-                    "U" => Ok(Direction::Up),
-                    "D" => Ok(Direction::Down),
-                    "L" => Ok(Direction::Left),
-                    "R" => Ok(Direction::Right),
-                    _ => Err(Error::from(ErrorKind::InvalidData)),
-                }?;
-                let count = number
-                    .parse::<usize>()
-                    .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
-
-                Ok(Movement { direction, count })
+fn count_tail_positions(movements: &[Movement], pieces_of_rope: usize) -> usize {
+    let mut bridge = RopeBridge::new(movements, pieces_of_rope);
+    let mut seen = HashSet::new();
+
+    seen.insert(bridge.tail());
+    while !bridge.is_done() {
+        bridge.step();
+        seen.insert(bridge.tail());
+    }
+
+    event!(
+        Level::Info,
+        "visited",
+        knots = pieces_of_rope,
+        positions = seen.len()
+    );
+    seen.len()
+}
+
+/// A rope following the motions of its head, one step of the head at a time.
+#[derive(Clone)]
+pub struct RopeBridge<'a> {
+    rope: Rope,
+    motions: &'a [Movement],
+    /// Index of the current motion.
+    motion: usize,
+    /// Steps of the current motion already made.
+    steps: usize,
+}
+
+impl<'a> RopeBridge<'a> {
+    /// A rope of `knots` knots, at least one, all starting at the origin.
+    pub fn new(motions: &'a [Movement], knots: usize) -> Self {
+        let mut rope = Rope {
+            position: Point::ORIGIN,
+            rest: None,
+        };
+
+        for _ in 1..knots {
+            rope = Rope {
+                position: Point::ORIGIN,
+                rest: Some(Box::new(rope)),
             }
+        }
+
+        let mut bridge = RopeBridge {
+            rope,
+            motions,
+            motion: 0,
+            steps: 0,
+        };
+        bridge.skip_finished_motions();
+        bridge
+    }
 
-            _ => Err(Error::from(ErrorKind::InvalidData)),
+    pub fn tail(&self) -> Point {
+        self.rope.get_tail()
+    }
+
+    /// The positions of the knots, head first.
+    fn knots(&self) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(Some(&self.rope), |knot| knot.rest.as_deref())
+            .map(|knot| knot.position)
+    }
+
+    fn progress(&self) -> String {
+        match self.motions.get(self.motion) {
+            Some(motion) => format!(
+                "Motion {} of {}: {} of {} steps made",
+                self.motion + 1,
+                self.motions.len(),
+                self.steps,
+                motion.count
+            ),
+            None => format!("All {} motions made", self.motions.len()),
+        }
+    }
+
+    /// The knots and the `visited` positions of the tail, in a frame whose top
+    /// left corner is at `min`.
+    fn frame(&self, min: Point, max: Point, visited: &HashSet<Point>) -> Frame {
+        let size = max - min;
+        let mut frame = Frame::new(size.x as usize + 1, size.y as usize + 1);
+        for &position in visited {
+            frame.set(position - min, Cell::new(Rgb(70, 70, 90), '#'));
+        }
+        frame.set(Point::ORIGIN - min, Cell::new(Rgb(110, 110, 130), 's'));
+
+        let knots: Vec<_> = self.knots().collect();
+        let last = knots.len() - 1;
+        // The head last, so that it covers the knots below it.
+        for (index, &knot) in knots.iter().enumerate().rev() {
+            let cell = match index {
+                0 => Cell::new(Rgb(230, 25, 75), 'H'),
+                _ if index == last => Cell::new(Rgb(67, 99, 216), 'T'),
+                _ => Cell::new(
+                    Rgb(255, 225, 25).mix(Rgb(67, 99, 216), index as f64 / last as f64),
+                    char::from_digit(index as u32 % 10, 10).unwrap_or('?'),
+                ),
+            };
+            frame.set(knot - min, cell);
+        }
+        frame.caption = self.progress();
+        frame
+    }
+
+    fn skip_finished_motions(&mut self) {
+        while self
+            .motions
+            .get(self.motion)
+            .is_some_and(|motion| self.steps == motion.count)
+        {
+            self.motion += 1;
+            self.steps = 0;
         }
     }
 }
 
-pub fn run(args: &[String]) -> std::io::Result<()> {
-    let file = File::open("input/day09.txt")?;
-    let reader = BufReader::new(file);
-
-    let pieces_of_rope: usize = args
-        .get(0)
-        .ok_or(Error::from(ErrorKind::InvalidData))?
-        .parse()
-        .map_err(|err| Error::new(ErrorKind::Other, err))?;
-
-    let mut positions = Rope {
-        position: Position { x: 0, y: 0 },
-        rest: None,
-    };
-
-    for _ in 0..(pieces_of_rope - 1) {
-        positions = Rope {
-            position: Position { x: 0, y: 0 },
-            rest: Some(Box::new(positions)),
+impl<'a> Simulation for RopeBridge<'a> {
+    type Snapshot = RopeBridge<'a>;
+
+    fn step(&mut self) {
+        let Some(motion) = self.motions.get(self.motion) else {
+            return;
+        };
+        self.rope.make_step(motion.direction);
+        self.steps += 1;
+        event!(
+            Level::Trace,
+            "step",
+            head = self.rope.position,
+            tail = self.tail()
+        );
+        if self.steps == motion.count {
+            event!(
+                Level::Debug,
+                "motion",
+                motion = self.motion + 1,
+                head = self.rope.position,
+                tail = self.tail()
+            );
         }
+        self.skip_finished_motions();
     }
 
-    let mut seen = HashSet::new();
+    fn is_done(&self) -> bool {
+        self.motion == self.motions.len()
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: &Self::Snapshot) {
+        self.clone_from(snapshot);
+    }
+
+    /// The current motion and the knots from head to tail.
+    fn dump(&self) -> String {
+        let knots: Vec<_> = self.knots().map(|knot| knot.to_string()).collect();
+        format!("{}\nKnots: {}\n", self.progress(), knots.join(" "))
+    }
+}
 
-    seen.insert(Position { x: 0, y: 0 });
-    for line in reader.lines() {
-        let Movement { direction, count } = line?.try_into()?;
-        for _ in 0..count {
-            positions.make_step(&direction);
-            seen.insert(positions.get_tail());
+/// The corners of the box holding every position of the head. The other
+/// knots only ever move towards the knot before them, so they stay inside.
+fn head_bounds(movements: &[Movement]) -> (Point, Point) {
+    let (mut min, mut max, mut head) = (Point::ORIGIN, Point::ORIGIN, Point::ORIGIN);
+    for movement in movements {
+        for _ in 0..movement.count {
+            head = head.step(movement.direction);
         }
+        min = Point::new(min.x.min(head.x), min.y.min(head.y));
+        max = Point::new(max.x.max(head.x), max.y.max(head.y));
     }
+    (min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::example_tests;
 
-    println!("Tail was at {} positions", seen.len());
+    example_tests! {
+        Day09;
+        example: part1 = 13, part2 = 1;
+        example2: part2 = 36;
+    }
 
-    Ok(())
+    #[test]
+    fn parse_should_skip_invalid_motions_when_lenient() {
+        let input = "R 4\nX 1\nU\nL 2\n";
+
+        assert_eq!(2, Day09::parse(input).unwrap().len());
+        assert!(Day09::parse_with(input, Strictness::Strict).is_err());
+    }
+
+    #[test]
+    fn options_should_reject_more_knots_than_fit() {
+        let given = [("knots".to_string(), "10000000000".to_string())];
+
+        let result = Options::resolve(Day09::DAY, Day09::OPTIONS, Part::Two, &given);
+
+        assert!(result.is_err());
+    }
 }
@@ -1,178 +1,320 @@
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
-use std::io::Error;
-use std::io::ErrorKind;
-use std::io::Lines;
+use crate::error::{ParseError, Result, Strictness};
+use crate::generate::Rng;
+use crate::grid::{Grid, Point};
+use crate::options::{OptionKind, OptionSpec, Options};
+use crate::parse::{self, literal, number, Parser};
+use crate::simulation::{self, Simulation};
+use crate::solution::{Answer, Part, Solution};
+use crate::trace::{event, Level};
+use crate::visualize::{self, Cell, Frame, Frames, Rgb};
 
 #[derive(Clone, Copy)]
-enum Instruction {
+pub enum Instruction {
     Noop,
     Add(i32),
 }
 
-impl TryFrom<String> for Instruction {
-    type Error = Error;
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        if value == "noop" {
-            return Ok(Self::Noop);
+impl TryFrom<&str> for Instruction {
+    /// The column where the line stops matching.
+    type Error = usize;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        let instruction = literal("noop")
+            .map(|_| Self::Noop)
+            .or(literal("addx ").then(number()).map(Self::Add));
+
+        parse::parse_all(value, instruction)
+    }
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "Cathode-Ray Tube";
+
+    /// The program run by the CPU.
+    type Input = Vec<Instruction>;
+
+    const GENERATOR: &'static [OptionSpec] = &[OptionSpec {
+        name: "cycles",
+        kind: OptionKind::Range {
+            min: 1,
+            max: 100_000,
+        },
+        defaults: ["240", "240"],
+        help: "number of cycles the program runs at least",
+    }];
+
+    fn parse_with(input: &str, strictness: Strictness) -> Result<Self::Input> {
+        let mut instructions = Vec::new();
+        for instruction in parse_instructions(input) {
+            match instruction {
+                Ok(instruction) => instructions.push(instruction),
+                Err(err) => strictness.check(err)?,
+            }
         }
-        let (maybe_addx, number) = value
-            .split_once(' ')
-            .ok_or(Error::from(ErrorKind::InvalidData))?;
-        if maybe_addx != "addx" {
-            return Err(Error::from(ErrorKind::InvalidData));
+        Ok(instructions)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        parse_instructions(input)
+            .filter_map(|instruction| instruction.err())
+            .collect()
+    }
+
+    fn generate(rng: &mut Rng, options: &Options) -> Option<String> {
+        let mut program = String::new();
+        let mut cycles = 0;
+        while cycles < options.integer("cycles") {
+            if rng.chance(0.4) {
+                program.push_str("noop\n");
+                cycles += 1;
+            } else {
+                let augend = rng.range(0..=30) as i32 - 15;
+                program.push_str(&format!("addx {}\n", augend));
+                cycles += 2;
+            }
         }
+        Some(program)
+    }
 
-        let x = number
-            .parse::<i32>()
-            .map_err(|parse_error| Error::new(ErrorKind::InvalidData, parse_error))?;
-        Ok(Self::Add(x))
+    fn part1(input: &Self::Input, _options: &Options) -> Answer {
+        part1(input).into()
+    }
+
+    fn part2(input: &Self::Input, _options: &Options) -> Answer {
+        part2(input).into()
     }
-}
 
-pub fn run(args: &[String]) -> std::io::Result<()> {
-    let file = File::open("input/day10.txt")?;
-    let reader = BufReader::new(file);
+    /// The screen after each cycle.
+    fn visualize<'a>(
+        input: &'a Self::Input,
+        _part: Part,
+        _options: &Options,
+    ) -> Option<Frames<'a>> {
+        Some(visualize::frames_of(
+            CathodRayTube::new(input),
+            CathodRayTube::frame,
+        ))
+    }
 
-    match args
-        .get(0)
-        .ok_or(Error::from(ErrorKind::InvalidData))?
-        .parse()
-        .map_err(|err| Error::new(ErrorKind::Other, err))?
-    {
-        1 => part1(reader),
-        2 => part2(reader),
-        _ => Err(Error::from(ErrorKind::InvalidData)),
+    fn describe(part: Part, _options: &Options, answer: &Answer) -> String {
+        match part {
+            Part::One => format!("Sum of signal strenghts is {}", answer),
+            Part::Two => answer.to_string(),
+        }
     }
 }
 
-fn part1(reader: BufReader<File>) -> std::io::Result<()> {
-    let mut cycle = 0;
-    let mut register_x = 1;
-    let mut sum_of_signal_strenghts = 0;
+fn parse_instructions(
+    input: &str,
+) -> impl Iterator<Item = std::result::Result<Instruction, ParseError>> + '_ {
+    parse::lines(input).map(|line| {
+        let instruction = Instruction::try_from(line.text)
+            .map_err(|column| line.error(Day10::DAY, column, "`noop` or `addx <n>`"))?;
+        event!(
+            Level::Trace,
+            "instruction",
+            line = line.number,
+            instruction = line.text
+        );
+        Ok(instruction)
+    })
+}
 
-    for line in reader.lines() {
-        let instruction: Instruction = line?.try_into()?;
+fn part1(instructions: &[Instruction]) -> i32 {
+    let mut crt = CathodRayTube::new(instructions);
+    let mut sum_of_signal_strenghts = 0;
 
-        match instruction {
-            Instruction::Noop => {
-                cycle += 1;
-                if cycle % 40 == 20 {
-                    sum_of_signal_strenghts += cycle * register_x;
-                }
-            }
-            Instruction::Add(augend) => {
-                cycle += 1;
-                if cycle % 40 == 20 {
-                    sum_of_signal_strenghts += cycle * register_x;
-                }
-                cycle += 1;
-                if cycle % 40 == 20 {
-                    sum_of_signal_strenghts += cycle * register_x;
-                }
-                register_x += augend;
-            }
+    while !crt.is_done() {
+        let cycle = crt.cycle as i32 + 1;
+        if cycle % 40 == 20 {
+            let signal_strength = cycle * crt.sprite.register_x;
+            event!(
+                Level::Debug,
+                "signal",
+                cycle = cycle,
+                x = crt.sprite.register_x,
+                strength = signal_strength
+            );
+            sum_of_signal_strenghts += signal_strength;
         }
+        crt.step();
     }
-    println!("Sum of signal strenghts is {}", sum_of_signal_strenghts);
-    Ok(())
+    sum_of_signal_strenghts
 }
 
 const DISPLAY_WIDTH: usize = 40;
 const DISPLAY_HEIGHT: usize = 6;
-struct Sprite {
-    lines: Lines<BufReader<File>>,
+#[derive(Clone)]
+struct Sprite<'a> {
+    instructions: std::slice::Iter<'a, Instruction>,
     next_add: Option<i32>,
     register_x: i32,
 }
 
-impl Sprite {
-    fn try_tick(&mut self) -> std::io::Result<bool> {
+impl<'a> Sprite<'a> {
+    fn tick(&mut self) -> bool {
         if let Some(add) = self.next_add {
             self.register_x += add;
             self.next_add = None;
-            return Ok(true);
+            return true;
         }
 
-        match self.lines.next() {
-            Some(Ok(x)) => {
-                if let Instruction::Add(add) = x.try_into()? {
-                    self.next_add = Some(add);
-                } else {
-                    self.next_add = None;
-                }
-                Ok(true)
+        match self.instructions.next() {
+            Some(Instruction::Add(add)) => {
+                self.next_add = Some(*add);
+                true
+            }
+            Some(Instruction::Noop) => {
+                self.next_add = None;
+                true
             }
-            Some(Err(err)) => Err(Error::new(ErrorKind::Other, err)),
-            None => Ok(false),
+            None => false,
         }
     }
 }
 
-struct CathodRayTube {
+/// The CPU running the program and drawing the screen, one cycle per step.
+#[derive(Clone)]
+pub struct CathodRayTube<'a> {
     cycle: usize,
-    sprite: Sprite,
-    pixels: [[u8; DISPLAY_WIDTH]; DISPLAY_HEIGHT],
+    sprite: Sprite<'a>,
+    pixels: Grid<bool>,
 }
 
-impl CathodRayTube {
-    fn new(lines: Lines<BufReader<File>>) -> CathodRayTube {
+impl<'a> CathodRayTube<'a> {
+    pub fn new(instructions: &'a [Instruction]) -> CathodRayTube<'a> {
         CathodRayTube {
             cycle: 0,
-            pixels: [[b'.'; DISPLAY_WIDTH]; DISPLAY_HEIGHT],
+            pixels: Grid::new(DISPLAY_WIDTH, DISPLAY_HEIGHT, false),
             sprite: Sprite {
                 register_x: 1,
                 next_add: None,
-                lines,
+                instructions: instructions.iter(),
             },
         }
     }
 
-    fn try_tick(&mut self) -> std::io::Result<bool> {
+    fn tick(&mut self) -> bool {
         let register_x = self.sprite.register_x % DISPLAY_WIDTH as i32;
-        let (x, y) = (
-            self.cycle % DISPLAY_WIDTH,
-            self.cycle / DISPLAY_WIDTH % DISPLAY_HEIGHT,
+        let beam = Point::new(
+            (self.cycle % DISPLAY_WIDTH) as i32,
+            (self.cycle / DISPLAY_WIDTH % DISPLAY_HEIGHT) as i32,
         );
 
-        let line_read = self.sprite.try_tick()?;
+        let line_read = self.sprite.tick();
         if line_read {
-            if (register_x - x as i32).abs() <= 1 {
-                self.pixels[y][x] = b'#';
-            } else {
-                self.pixels[y][x] = b'.';
-            }
+            self.pixels[beam] = (register_x - beam.x).abs() <= 1;
         }
 
         self.cycle += 1;
 
-        Ok(line_read)
+        line_read
     }
 }
 
-impl std::fmt::Display for CathodRayTube {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in &self.pixels {
-            writeln!(f, "{}", String::from_utf8_lossy(row))?;
+impl<'a> CathodRayTube<'a> {
+    /// The screen with the pixels drawn so far, the sprite and the beam.
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::new(DISPLAY_WIDTH, DISPLAY_HEIGHT);
+        let beam = Point::new(
+            (self.cycle % DISPLAY_WIDTH) as i32,
+            (self.cycle / DISPLAY_WIDTH % DISPLAY_HEIGHT) as i32,
+        );
+        for x in self.sprite.register_x - 1..=self.sprite.register_x + 1 {
+            frame.set(Point::new(x, beam.y), Cell::new(Rgb(40, 50, 110), ' '));
+        }
+        for point in self.pixels.points().filter(|&point| self.pixels[point]) {
+            frame.set(point, Cell::new(Rgb(60, 220, 90), '#'));
         }
-        Ok(())
+        if !self.is_done() {
+            frame.set(beam, Cell::new(Rgb(255, 225, 25), '|'));
+        }
+        frame.caption = format!("After cycle {}: X = {}", self.cycle, self.sprite.register_x);
+        frame
+    }
+}
+
+impl<'a> Simulation for CathodRayTube<'a> {
+    type Snapshot = CathodRayTube<'a>;
+
+    fn step(&mut self) {
+        if !self.is_done() {
+            self.tick();
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.sprite.next_add.is_none() && self.sprite.instructions.len() == 0
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: &Self::Snapshot) {
+        self.clone_from(snapshot);
+    }
+
+    /// The cycles so far, the register and the screen.
+    fn dump(&self) -> String {
+        format!(
+            "After cycle {}: X = {}\n{}",
+            self.cycle, self.sprite.register_x, self
+        )
+    }
+}
+
+impl<'a> std::fmt::Display for CathodRayTube<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let image = self.pixels.render(|&lit| if lit { '#' } else { '.' });
+        write!(f, "{}", image)
     }
 }
 
-fn part2(reader: BufReader<File>) -> std::io::Result<()> {
-    let mut crt = CathodRayTube::new(reader.lines());
-    while crt.try_tick()? {}
-    draw_display(&crt.pixels)?;
-    Ok(())
+fn part2(instructions: &[Instruction]) -> String {
+    let mut crt = CathodRayTube::new(instructions);
+    simulation::run_to_end(&mut crt);
+    crt.to_string().trim_end().to_owned()
 }
 
-fn draw_display(display: &[[u8; DISPLAY_WIDTH]; DISPLAY_HEIGHT]) -> std::io::Result<()> {
-    let mut out = std::io::stdout();
-    for row in display {
-        out.write_all(row)?;
-        out.write_all(b"\n")?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::example_tests;
+
+    const EXAMPLE_IMAGE: &str = concat!(
+        "##..##..##..##..##..##..##..##..##..##..\n",
+        "###...###...###...###...###...###...###.\n",
+        "####....####....####....####....####....\n",
+        "#####.....#####.....#####.....#####.....\n",
+        "######......######......######......####\n",
+        "#######.......#######.......#######....."
+    );
+
+    example_tests! {
+        Day10;
+        example: part1 = 13140, part2 = EXAMPLE_IMAGE;
+    }
+
+    #[test]
+    fn check_should_report_every_bad_instruction() {
+        let problems = Day10::check("noop\naddx 3\nmulx 2\naddx\nnoop\n");
+
+        let positions: Vec<_> = problems
+            .iter()
+            .map(|problem| (problem.line, problem.column))
+            .collect();
+        assert_eq!(vec![(3, 1), (4, 5)], positions);
+    }
+
+    #[test]
+    fn parse_should_skip_invalid_instructions_when_lenient() {
+        let input = "noop\naddx 3\nmulx 2\naddx\nnoop\n";
+
+        assert_eq!(3, Day10::parse(input).unwrap().len());
+        assert!(Day10::parse_with(input, Strictness::Strict).is_err());
     }
-    out.flush()?;
-    Ok(())
 }
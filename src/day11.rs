@@ -1,163 +1,572 @@
 use std::{
     cmp::Reverse,
     collections::{BinaryHeap, VecDeque},
-    fs::File,
-    io::{BufRead, BufReader, Error, ErrorKind, Lines, Result},
+    fmt,
 };
 
-pub fn run(args: &[String]) -> std::io::Result<()> {
-    let file = File::open("input/day11.txt")?;
-    let reader = BufReader::new(file);
+use crate::error::{ParseError, Result, Strictness};
+use crate::generate::Rng;
+use crate::options::{OptionKind, OptionSpec, Options};
+use crate::parse::{self, literal, number, separated, Line, Parser, Section};
+use crate::simulation::{self, Simulation};
+use crate::solution::{Answer, Part, Solution};
+use crate::trace::{event, Level};
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    const TITLE: &'static str = "Monkey in the Middle";
+
+    /// The monkeys with the items they hold at the start.
+    type Input = Vec<Monkey>;
+
+    const GENERATOR: &'static [OptionSpec] = &[
+        OptionSpec {
+            name: "monkeys",
+            kind: OptionKind::Range { min: 2, max: 100 },
+            defaults: ["8", "8"],
+            help: "number of monkeys",
+        },
+        OptionSpec {
+            name: "items",
+            kind: OptionKind::Range { min: 1, max: 100 },
+            defaults: ["6", "6"],
+            help: "most items a monkey starts with",
+        },
+    ];
+
+    fn parse_with(input: &str, strictness: Strictness) -> Result<Self::Input> {
+        parse_monkeys(input, strictness)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        check_monkeys(input)
+    }
+
+    /// Monkeys whose worry levels fit into a `usize` in both parts.
+    fn generate(rng: &mut Rng, options: &Options) -> Option<String> {
+        let count = options.integer("monkeys") as usize;
+        let items = options.integer("items") as u64;
+        let mut attempt = 0;
+        let monkeys = loop {
+            // Only adding keeps part 1 small if growing worry levels keep failing.
+            let monkeys = random_monkeys(rng, count, items, attempt < 10);
+            if part1_overflow(monkeys.clone()).is_none() {
+                break monkeys;
+            }
+            attempt += 1;
+        };
+
+        let sections: Vec<_> = monkeys
+            .iter()
+            .enumerate()
+            .map(|(index, monkey)| {
+                let items: Vec<_> = monkey.items.iter().map(ToString::to_string).collect();
+                format!(
+                    "Monkey {}:\n  Starting items: {}\n  Operation: new = old {}\n  \
+                     Test: divisible by {}\n    If true: throw to monkey {}\n    \
+                     If false: throw to monkey {}\n",
+                    index,
+                    items.join(", "),
+                    monkey.operation,
+                    monkey.test_divisible_by,
+                    monkey.throw_to_if_true,
+                    monkey.throw_to_if_false
+                )
+            })
+            .collect();
+        Some(sections.join("\n"))
+    }
 
-    let monkeys = parse_monkeys(reader.lines());
+    fn part1(input: &Self::Input, _options: &Options) -> Answer {
+        part1(input.clone()).into()
+    }
 
-    match args
-        .get(0)
-        .ok_or(Error::from(ErrorKind::InvalidData))?
-        .parse()
-        .map_err(|err| Error::new(ErrorKind::Other, err))?
-    {
-        1 => part1(monkeys),
-        2 => part2(monkeys),
-        _ => Err(Error::from(ErrorKind::InvalidData)),
+    fn part2(input: &Self::Input, _options: &Options) -> Answer {
+        part2(input.clone()).into()
+    }
+
+    fn describe(_part: Part, _options: &Options, answer: &Answer) -> String {
+        format!("Worry level is: {}", answer)
     }
 }
 
+#[derive(Clone)]
 pub struct Monkey {
     items: VecDeque<usize>,
-    operation: Box<dyn Fn(usize) -> usize>,
+    operation: Operation,
     test_divisible_by: usize,
     throw_to_if_true: usize,
     throw_to_if_false: usize,
     number_of_inspections: usize,
 }
 
-pub fn parse_monkeys<B: BufRead>(lines: Lines<B>) -> Vec<Monkey> {
-    let mut lines = lines;
+#[derive(Clone, Copy)]
+pub enum Operation {
+    Add(usize),
+    Multiply(usize),
+    Double,
+    Square,
+}
+
+impl Operation {
+    /// Like [`Operation::apply`], or `None` if the result does not fit.
+    pub fn checked_apply(self, old: usize) -> Option<usize> {
+        match self {
+            Operation::Add(n) => old.checked_add(n),
+            Operation::Multiply(n) => old.checked_mul(n),
+            Operation::Double => old.checked_add(old),
+            Operation::Square => old.checked_mul(old),
+        }
+    }
+
+    pub fn apply(self, old: usize) -> usize {
+        match self {
+            Operation::Add(n) => old + n,
+            Operation::Multiply(n) => old * n,
+            Operation::Double => old + old,
+            Operation::Square => old * old,
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    /// The right-hand side of the operation after `old`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Add(n) => write!(f, "+ {}", n),
+            Operation::Multiply(n) => write!(f, "* {}", n),
+            Operation::Double => write!(f, "+ old"),
+            Operation::Square => write!(f, "* old"),
+        }
+    }
+}
+
+/// `count` monkeys throwing to each other. Part 2 keeps worry levels below the
+/// product of the divisors, so it is kept below 2^32 for squaring to fit.
+fn random_monkeys(rng: &mut Rng, count: usize, items: u64, growing: bool) -> Vec<Monkey> {
+    const PRIMES: [usize; 9] = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+    let mut product_of_divisors = 1usize;
+    (0..count)
+        .map(|index| {
+            let fitting: Vec<_> = PRIMES
+                .iter()
+                .filter(|&&prime| product_of_divisors * prime <= 1 << 32)
+                .collect();
+            let test_divisible_by = if fitting.is_empty() {
+                1
+            } else {
+                **rng.pick(&fitting)
+            };
+            product_of_divisors *= test_divisible_by;
+
+            let operation = match rng.below(100) {
+                _ if !growing => Operation::Add(rng.range(1..=8) as usize),
+                0..=49 => Operation::Add(rng.range(1..=8) as usize),
+                50..=84 => Operation::Multiply(rng.range(2..=19) as usize),
+                _ => Operation::Square,
+            };
+            let items = (0..rng.range(1..=items))
+                .map(|_| rng.range(50..=99) as usize)
+                .collect();
+            let mut other_monkey = || (index + 1 + rng.below(count - 1)) % count;
+            Monkey {
+                items,
+                operation,
+                test_divisible_by,
+                throw_to_if_true: other_monkey(),
+                throw_to_if_false: other_monkey(),
+                number_of_inspections: 0,
+            }
+        })
+        .collect()
+}
+
+/// The first monkey whose operation makes a worry level of part 1 overflow a
+/// `usize`, if any.
+fn part1_overflow(mut monkeys: Vec<Monkey>) -> Option<usize> {
+    for _ in 0..20 {
+        for i in 0..monkeys.len() {
+            while let Some(item) = monkeys[i].items.pop_front() {
+                let Some(after_inspection) = monkeys[i].operation.checked_apply(item) else {
+                    return Some(i);
+                };
+                let after_inspection = after_inspection / 3;
+                let throw_to = if after_inspection.is_multiple_of(monkeys[i].test_divisible_by) {
+                    monkeys[i].throw_to_if_true
+                } else {
+                    monkeys[i].throw_to_if_false
+                };
+                monkeys[throw_to].items.push_back(after_inspection);
+            }
+        }
+    }
+    None
+}
+
+/// Parses the monkeys separated by empty lines. In lenient mode it stops at the
+/// first monkey that cannot be parsed. Monkeys that cannot be simulated, like
+/// ones throwing to monkeys that do not exist, are an error in both modes.
+pub fn parse_monkeys(input: &str, strictness: Strictness) -> Result<Vec<Monkey>> {
+    let sections = parse::sections(input);
     let mut monkeys = Vec::new();
-    while let Some(monkey) = parse_monkey(&mut lines) {
-        monkeys.push(monkey);
+    for section in &sections {
+        match parse_monkey(section) {
+            Ok(monkey) => monkeys.push(monkey),
+            Err(errors) => {
+                strictness.check(errors.into_iter().next().expect("failures have errors"))?;
+                break;
+            }
+        }
+    }
+
+    for (index, (section, monkey)) in sections.iter().zip(&monkeys).enumerate() {
+        if let Some(problem) = unsolvable(section, index, monkeys.len(), monkey)
+            .into_iter()
+            .next()
+        {
+            return Err(problem.into());
+        }
+    }
+    if let Some(problem) = overflowing(&sections, &monkeys).into_iter().next() {
+        return Err(problem.into());
+    }
+    Ok(monkeys)
+}
+
+/// Reports a test dividing by zero and throws to the monkey itself or to
+/// monkeys that do not exist, for the monkey at `index` of `count`.
+fn unsolvable(section: &Section, index: usize, count: usize, monkey: &Monkey) -> Vec<ParseError> {
+    let mut problems = Vec::new();
+    if monkey.test_divisible_by == 0 {
+        let line = section.line(3);
+        problems.push(line.error(Day11::DAY, last_word(&line), "a divisor greater than 0"));
+    }
+
+    let expected = format!(
+        "another monkey than {}, from 0 to {}",
+        index,
+        count.saturating_sub(1)
+    );
+    for (line, target) in [(4, monkey.throw_to_if_true), (5, monkey.throw_to_if_false)] {
+        if target == index || target >= count {
+            let line = section.line(line);
+            problems.push(line.error(Day11::DAY, last_word(&line), &expected));
+        }
+    }
+    problems
+}
+
+/// Reports divisors whose product does not fit in a `usize`, and operations
+/// making worry levels overflow in part 1 or, below the product of the
+/// divisors, in part 2. The monkeys have to be solvable otherwise.
+fn overflowing(sections: &[Section], monkeys: &[Monkey]) -> Vec<ParseError> {
+    let mut product = 1usize;
+    for (section, monkey) in sections.iter().zip(monkeys) {
+        let Some(next) = product.checked_mul(monkey.test_divisible_by) else {
+            let line = section.line(3);
+            return vec![line.error(
+                Day11::DAY,
+                last_word(&line),
+                "a divisor keeping the product of all divisors within a usize",
+            )];
+        };
+        product = next;
+    }
+
+    let expected = format!(
+        "an operation keeping worry levels below {} within a usize",
+        product
+    );
+    let mut problems = Vec::new();
+    for (section, monkey) in sections.iter().zip(monkeys) {
+        if monkey.operation.checked_apply(product - 1).is_none() {
+            let line = section.line(2);
+            problems.push(line.error(Day11::DAY, last_word(&line), &expected));
+        }
+    }
+    if problems.is_empty() {
+        if let Some(index) = part1_overflow(monkeys.to_vec()) {
+            let line = sections[index].line(2);
+            problems.push(line.error(
+                Day11::DAY,
+                last_word(&line),
+                "an operation keeping the worry levels of part 1 within a usize",
+            ));
+        }
     }
-    monkeys
+    problems
 }
 
-pub fn parse_monkey<B: BufRead>(lines: &mut Lines<B>) -> Option<Monkey> {
-    _ = lines.next();
+/// The column of the last word of `line`.
+fn last_word(line: &Line) -> usize {
+    line.text.rfind(' ').map_or(1, |space| space + 2)
+}
 
-    let items = {
-        let items_str = lines.next().and_then(Result::ok)?["  Starting items: ".len()..].to_owned();
-        let mut items = VecDeque::new();
-        for item_str in items_str.split(", ") {
-            items.push_back(item_str.parse().ok()?);
+/// Reports every malformed line, monkeys numbered out of order, tests dividing
+/// by zero and monkeys throwing to themselves or to monkeys that do not exist.
+/// Only if there are none, it reports worry levels that overflow.
+fn check_monkeys(input: &str) -> Vec<ParseError> {
+    let sections = parse::sections(input);
+    if sections.is_empty() {
+        return vec![ParseError::new(Day11::DAY, 1, 1, "", "`Monkey 0:`")];
+    }
+
+    let mut problems = Vec::new();
+    let mut monkeys = Vec::new();
+    for (index, section) in sections.iter().enumerate() {
+        let header = section.line(0);
+        if let Ok(number) = header.parse(monkey_header()) {
+            if number != index {
+                problems.push(header.error(
+                    Day11::DAY,
+                    8,
+                    &format!("`Monkey {}:` as monkeys are numbered in order", index),
+                ));
+            }
         }
-        Some(items)
-    }?;
-    let operation = parse_operation(lines)?;
-    let test_divisible_by = parse_number_after(lines, "  Test: divisible by ")?;
-    let throw_to_if_true = parse_number_after(lines, "    If true: throw to monkey ")?;
-    let throw_to_if_false = parse_number_after(lines, "    If false: throw to monkey ")?;
 
-    _ = lines.next();
+        match parse_monkey(section) {
+            Ok(monkey) => {
+                problems.extend(unsolvable(section, index, sections.len(), &monkey));
+                monkeys.push(monkey);
+            }
+            Err(errors) => problems.extend(errors),
+        }
+    }
+    if problems.is_empty() {
+        problems = overflowing(&sections, &monkeys);
+    }
+    problems
+}
 
-    Some(Monkey {
+/// Parses the six lines describing a monkey, or returns every malformed one.
+pub fn parse_monkey(section: &Section) -> std::result::Result<Monkey, Vec<ParseError>> {
+    let mut errors = Vec::new();
+    let header = parse_line(section, 0, monkey_header(), "`Monkey <n>:`", &mut errors);
+    let items = parse_line(
+        section,
+        1,
+        literal("  Starting items: ").then(separated(number(), ", ")),
+        "`  Starting items: <worry level>, ...`",
+        &mut errors,
+    );
+    let operation = parse_line(
+        section,
+        2,
+        literal("  Operation: new = old ").then(operation()),
+        "`  Operation: new = old <+|*> <n|old>`",
+        &mut errors,
+    );
+    let test_divisible_by = parse_line(
+        section,
+        3,
+        literal("  Test: divisible by ").then(number()),
+        "`  Test: divisible by <n>`",
+        &mut errors,
+    );
+    let throw_to_if_true = parse_line(
+        section,
+        4,
+        literal("    If true: throw to monkey ").then(number()),
+        "`    If true: throw to monkey <n>`",
+        &mut errors,
+    );
+    let throw_to_if_false = parse_line(
+        section,
+        5,
+        literal("    If false: throw to monkey ").then(number()),
+        "`    If false: throw to monkey <n>`",
+        &mut errors,
+    );
+
+    match (
+        header,
         items,
         operation,
         test_divisible_by,
         throw_to_if_true,
         throw_to_if_false,
-        number_of_inspections: 0,
-    })
+    ) {
+        (
+            Some(_),
+            Some(items),
+            Some(operation),
+            Some(test_divisible_by),
+            Some(throw_to_if_true),
+            Some(throw_to_if_false),
+        ) => Ok(Monkey {
+            items: items.into(),
+            operation,
+            test_divisible_by,
+            throw_to_if_true,
+            throw_to_if_false,
+            number_of_inspections: 0,
+        }),
+        _ => Err(errors),
+    }
 }
 
-fn parse_number_after<B: BufRead>(reader: &mut Lines<B>, s: &str) -> Option<usize> {
-    reader.next().and_then(Result::ok)?[s.len()..].parse().ok()
+/// Parses the line at `index`, adding it to `errors` if it is malformed.
+fn parse_line<'a, T>(
+    section: &Section<'a>,
+    index: usize,
+    parser: impl Parser<'a, T>,
+    expected: &str,
+    errors: &mut Vec<ParseError>,
+) -> Option<T> {
+    let line = section.line(index);
+    line.parse(parser)
+        .map_err(|column| errors.push(line.error(Day11::DAY, column, expected)))
+        .ok()
 }
 
-fn parse_operation<B: BufRead>(reader: &mut Lines<B>) -> Option<Box<dyn Fn(usize) -> usize>> {
-    let operation_str =
-        reader.next().and_then(Result::ok)?["  Operation: new = old ".len()..].to_owned();
-
-    let (op, arg) = operation_str.split_once(' ')?;
-    match (op, arg.parse::<usize>().ok()) {
-        ("+", Some(n)) => Some(Box::new(move |x| x + n)),
-        ("*", Some(n)) => Some(Box::new(move |x| x * n)),
-        ("+", None) => Some(Box::new(|x| x + x)),
-        ("*", None) => Some(Box::new(|x| x * x)),
-        _ => None,
-    }
+fn monkey_header<'a>() -> impl Parser<'a, usize> {
+    literal("Monkey ").then(number()).skip(literal(":"))
 }
 
-fn part1(monkeys: Vec<Monkey>) -> Result<()> {
-    const NUMBER_OF_ROUNDS: i32 = 20;
-    let mut monkeys = monkeys;
-
-    for _ in 0..NUMBER_OF_ROUNDS {
-        for i in 0..monkeys.len() {
-            while let Some(item) = monkeys[i].items.pop_front() {
-                monkeys[i].number_of_inspections += 1;
+fn operation<'a>() -> impl Parser<'a, Operation> {
+    literal("+ old")
+        .map(|_| Operation::Double)
+        .or(literal("* old").map(|_| Operation::Square))
+        .or(literal("+ ").then(number()).map(Operation::Add))
+        .or(literal("* ").then(number()).map(Operation::Multiply))
+}
 
-                let after_inspection = (monkeys[i].operation)(item) / 3;
+fn part1(monkeys: Vec<Monkey>) -> usize {
+    monkey_business(Rounds::new(monkeys, Relief::DivideByThree, 20))
+}
 
-                let throw_to = if after_inspection % monkeys[i].test_divisible_by == 0 {
-                    monkeys[i].throw_to_if_true
-                } else {
-                    monkeys[i].throw_to_if_false
-                };
+fn part2(monkeys: Vec<Monkey>) -> usize {
+    monkey_business(Rounds::new(monkeys, Relief::ProductOfDivisors, 10_000))
+}
 
-                monkeys[throw_to].items.push_back(after_inspection);
-            }
-        }
-    }
+fn monkey_business(mut rounds: Rounds) -> usize {
+    simulation::run_to_end(&mut rounds);
 
-    for (i, m) in monkeys.iter().enumerate() {
-        println!(
-            "Monkey {} inspected items {} times.",
-            i, m.number_of_inspections
+    for (i, m) in rounds.monkeys.iter().enumerate() {
+        event!(
+            Level::Info,
+            "inspections",
+            monkey = i,
+            count = m.number_of_inspections
         );
     }
 
-    let (x, y) = find_top_two(monkeys.iter().map(|m| m.number_of_inspections));
-    println!("Worry level is: {}", x * y);
+    let (x, y) = find_top_two(rounds.monkeys.iter().map(|m| m.number_of_inspections));
+    x * y
+}
 
-    Ok(())
+/// How worry levels are kept in check after an inspection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relief {
+    /// Divided by three, because the item was not damaged.
+    DivideByThree,
+    /// Taken modulo the product of the divisors the monkeys test for, which
+    /// keeps the outcome of every test.
+    ProductOfDivisors,
 }
 
-fn part2(monkeys: Vec<Monkey>) -> std::io::Result<()> {
-    const NUMBER_OF_ROUNDS: i32 = 10_000;
-    let mut monkeys = monkeys;
+/// Monkeys throwing items to each other, one round per step.
+#[derive(Clone)]
+pub struct Rounds {
+    monkeys: Vec<Monkey>,
+    relief: Relief,
+    product_of_divisibility_tests: usize,
+    round: usize,
+    rounds: usize,
+}
+
+impl Rounds {
+    /// Monkeys as parsed by [`parse_monkeys`], whose worry levels are known to
+    /// fit.
+    pub fn new(mut monkeys: Vec<Monkey>, relief: Relief, rounds: usize) -> Self {
+        let product_of_divisibility_tests = monkeys.iter().map(|m| m.test_divisible_by).product();
+        if relief == Relief::ProductOfDivisors {
+            // Keeps the starting items below the product as well.
+            for monkey in &mut monkeys {
+                for item in &mut monkey.items {
+                    *item %= product_of_divisibility_tests;
+                }
+            }
+        }
+        Rounds {
+            product_of_divisibility_tests,
+            monkeys,
+            relief,
+            round: 0,
+            rounds,
+        }
+    }
+}
 
-    let product_of_divisibility_tests: usize =
-        monkeys.iter().map(|m| m.test_divisible_by).product();
+impl Simulation for Rounds {
+    type Snapshot = Rounds;
 
-    for _ in 0..NUMBER_OF_ROUNDS {
+    fn step(&mut self) {
+        if self.is_done() {
+            return;
+        }
+        let monkeys = &mut self.monkeys;
         for i in 0..monkeys.len() {
             while let Some(item) = monkeys[i].items.pop_front() {
                 monkeys[i].number_of_inspections += 1;
 
-                let after_inspection = (monkeys[i].operation)(item) % product_of_divisibility_tests;
+                let after_inspection = match self.relief {
+                    Relief::DivideByThree => monkeys[i].operation.apply(item) / 3,
+                    Relief::ProductOfDivisors => {
+                        monkeys[i].operation.apply(item) % self.product_of_divisibility_tests
+                    }
+                };
 
-                let throw_to = if after_inspection % monkeys[i].test_divisible_by == 0 {
+                let throw_to = if after_inspection.is_multiple_of(monkeys[i].test_divisible_by) {
                     monkeys[i].throw_to_if_true
                 } else {
                     monkeys[i].throw_to_if_false
                 };
 
+                event!(
+                    Level::Trace,
+                    "throw",
+                    monkey = i,
+                    worry = after_inspection,
+                    to = throw_to
+                );
                 monkeys[throw_to].items.push_back(after_inspection);
             }
         }
+        self.round += 1;
+        event!(Level::Debug, "round", round = self.round);
     }
 
-    for (i, m) in monkeys.iter().enumerate() {
-        println!(
-            "Monkey {} inspected items {} times.",
-            i, m.number_of_inspections
-        );
+    fn is_done(&self) -> bool {
+        self.round == self.rounds
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.clone()
     }
 
-    let (x, y) = find_top_two(monkeys.iter().map(|m| m.number_of_inspections));
-    println!("Worry level is: {}", x * y);
+    fn restore(&mut self, snapshot: &Self::Snapshot) {
+        self.clone_from(snapshot);
+    }
 
-    Ok(())
+    /// The items each monkey holds and how many it inspected, like in the
+    /// puzzle.
+    fn dump(&self) -> String {
+        let mut dump = format!("After round {} of {}:\n", self.round, self.rounds);
+        for (i, m) in self.monkeys.iter().enumerate() {
+            let items: Vec<_> = m.items.iter().map(ToString::to_string).collect();
+            dump += &format!(
+                "Monkey {} ({} inspections): {}\n",
+                i,
+                m.number_of_inspections,
+                items.join(", ")
+            );
+        }
+        dump
+    }
 }
 
 fn find_top_two<I>(vals: I) -> (usize, usize)
@@ -184,37 +593,14 @@ where
 mod tests {
     use itertools::Itertools;
 
-    use crate::day11::*;
-    use std::{
-        io::{BufReader, Read},
-        slice::Iter,
-    };
-
-    use super::parse_monkey;
-
-    struct StringReader<'a> {
-        iter: Iter<'a, u8>,
-    }
-
-    impl<'a> StringReader<'a> {
-        pub fn new(data: &'a str) -> Self {
-            Self {
-                iter: data.as_bytes().iter(),
-            }
-        }
-    }
+    use super::*;
+    use crate::error::Error;
+    use crate::parse;
+    use crate::testing::example_tests;
 
-    impl<'a> Read for StringReader<'a> {
-        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-            for i in 0..buf.len() {
-                if let Some(x) = self.iter.next() {
-                    buf[i] = *x;
-                } else {
-                    return Ok(i);
-                }
-            }
-            Ok(buf.len())
-        }
+    example_tests! {
+        Day11;
+        example: part1 = 10605, part2 = 2713310158_i64;
     }
 
     #[test]
@@ -228,10 +614,9 @@ mod tests {
             "    If true: throw to monkey 7\n",
             "    If false: throw to monkey 8\n"
         );
-        let reader = BufReader::new(StringReader::new(monkey_string));
 
         // Act
-        let monkey = parse_monkey(&mut reader.lines());
+        let monkey = parse_monkey(&parse::sections(monkey_string)[0]);
 
         // Assert
         let monkey = monkey.unwrap();
@@ -240,7 +625,7 @@ mod tests {
         let op = monkey.operation;
 
         for i in 0..100 {
-            assert_eq!(i + 5, op(i));
+            assert_eq!(i + 5, op.apply(i));
         }
 
         assert_eq!(6, monkey.test_divisible_by);
@@ -259,10 +644,9 @@ mod tests {
             "    If true: throw to monkey 6\n",
             "    If false: throw to monkey 7\n"
         );
-        let reader = BufReader::new(StringReader::new(monkey_string));
 
         // Act
-        let monkey = parse_monkey(&mut reader.lines());
+        let monkey = parse_monkey(&parse::sections(monkey_string)[0]);
 
         // Assert
         let monkey = monkey.unwrap();
@@ -271,7 +655,7 @@ mod tests {
         let op = monkey.operation;
 
         for i in 0..100 {
-            assert_eq!(i * 7, op(i));
+            assert_eq!(i * 7, op.apply(i));
         }
 
         assert_eq!(19, monkey.test_divisible_by);
@@ -288,10 +672,9 @@ mod tests {
             "    If true: throw to monkey 6\n",
             "    If false: throw to monkey 7\n"
         );
-        let reader = BufReader::new(StringReader::new(monkey_string));
 
         // Act
-        let monkey = parse_monkey(&mut reader.lines());
+        let monkey = parse_monkey(&parse::sections(monkey_string)[0]);
 
         // Assert
         let monkey = monkey.unwrap();
@@ -299,9 +682,99 @@ mod tests {
 
         let op = monkey.operation;
         for i in 0..100 {
-            assert_eq!(i * i, op(i));
+            assert_eq!(i * i, op.apply(i));
         }
 
         assert_eq!(19, monkey.test_divisible_by);
     }
+
+    /// Two monkeys, the first testing for `divisor` and throwing to `if_true`
+    /// and `if_false`.
+    fn two_monkeys(divisor: usize, if_true: usize, if_false: usize) -> String {
+        format!(
+            "Monkey 0:\n  Starting items: 79\n  Operation: new = old * 19\n  \
+             Test: divisible by {}\n    If true: throw to monkey {}\n    \
+             If false: throw to monkey {}\n\nMonkey 1:\n  Starting items: 54\n  \
+             Operation: new = old + 6\n  Test: divisible by 19\n    \
+             If true: throw to monkey 0\n    If false: throw to monkey 0\n",
+            divisor, if_true, if_false
+        )
+    }
+
+    /// The line and column of the error of parsing `input` strictly.
+    fn strict_error(input: &str) -> (usize, usize) {
+        match parse_monkeys(input, Strictness::Strict) {
+            Err(Error::Parse(err)) => (err.line, err.column),
+            Err(err) => panic!("unexpected error {}", err),
+            Ok(_) => panic!("parsed monkeys that cannot be simulated"),
+        }
+    }
+
+    #[test]
+    fn parse_monkeys_should_reject_dividing_by_zero() {
+        assert_eq!((4, 22), strict_error(&two_monkeys(0, 1, 1)));
+    }
+
+    #[test]
+    fn parse_monkeys_should_reject_missing_monkeys() {
+        assert_eq!((5, 30), strict_error(&two_monkeys(23, 7, 1)));
+        assert!(parse_monkeys(&two_monkeys(23, 7, 1), Strictness::Lenient).is_err());
+    }
+
+    #[test]
+    fn parse_monkeys_should_reject_throwing_to_itself() {
+        assert_eq!((6, 31), strict_error(&two_monkeys(23, 1, 0)));
+        assert!(parse_monkeys(&two_monkeys(23, 1, 1), Strictness::Strict).is_ok());
+    }
+
+    #[test]
+    fn parse_monkeys_should_reject_divisors_whose_product_overflows() {
+        let input = two_monkeys(1 << 40, 1, 1).replace("divisible by 19", "divisible by 16777216");
+
+        assert_eq!((11, 22), strict_error(&input));
+    }
+
+    #[test]
+    fn parse_monkeys_should_reject_operations_that_overflow() {
+        let input = two_monkeys(1 << 31, 1, 1)
+            .replace("divisible by 19", "divisible by 1073741824")
+            .replace("old * 19", "old * old");
+
+        assert_eq!((3, 26), strict_error(&input));
+        assert_eq!(1, Day11::check(&input).len());
+
+        let input = two_monkeys(23, 1, 1)
+            .replace("Starting items: 79", "Starting items: 1152921504606846976");
+        assert_eq!((3, 26), strict_error(&input));
+    }
+
+    #[test]
+    fn check_should_report_lines_indices_divisors_and_targets() {
+        let input = concat!(
+            "Monkey 0:\n",
+            "  Starting items: 79, 98\n",
+            "  Operation: new = old ^ 2\n",
+            "  Test: divisible by x\n",
+            "    If true: throw to monkey 1\n",
+            "    If false: throw to monkey 1\n",
+            "\n",
+            "Monkey 2:\n",
+            "  Starting items: 54\n",
+            "  Operation: new = old + 6\n",
+            "  Test: divisible by 0\n",
+            "    If true: throw to monkey 1\n",
+            "    If false: throw to monkey 5\n",
+        );
+
+        let problems = Day11::check(input);
+
+        let positions: Vec<_> = problems
+            .iter()
+            .map(|problem| (problem.line, problem.column))
+            .collect();
+        assert_eq!(
+            vec![(3, 24), (4, 22), (8, 8), (11, 22), (12, 30), (13, 31)],
+            positions
+        );
+    }
 }
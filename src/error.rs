@@ -0,0 +1,127 @@
+use std::fmt;
+
+use crate::registry;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Every error of the crate.
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    /// The puzzle input does not have the expected shape.
+    Parse(ParseError),
+    /// The requested day is not in the registry for the year.
+    UnknownDay {
+        year: u16,
+        day: String,
+    },
+    UnknownPart(String),
+    /// A day option is unknown or has an invalid value.
+    InvalidOption(String),
+    /// The command line could not be understood; the message includes the usage.
+    Usage(String),
+    /// A command ran but did not succeed, e.g. some answers were wrong.
+    Failed(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Parse(err) => write!(f, "{}", err),
+            Error::UnknownDay { year, day } => write!(
+                f,
+                "Day {} of {} not implemented. Available days: {}",
+                day,
+                year,
+                registry::available_days(*year)
+            ),
+            Error::UnknownPart(part) => write!(f, "Unknown part {}, expected 1 or 2", part),
+            Error::InvalidOption(message) | Error::Usage(message) | Error::Failed(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
+
+/// A malformed line of a puzzle input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    /// 1-based number of the offending line.
+    pub line: usize,
+    /// 1-based column, in characters, where the line stops matching.
+    pub column: usize,
+    /// The offending line.
+    pub text: String,
+    /// Description of what the line should look like.
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(day: u8, line: usize, column: usize, text: &str, expected: &str) -> Self {
+        ParseError {
+            day,
+            line,
+            column,
+            text: text.to_owned(),
+            expected: expected.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day {:02}, line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected
+        )?;
+        writeln!(f, "    {}", self.text)?;
+        write!(f, "    {}^", " ".repeat(self.column.saturating_sub(1)))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// How parsers treat lines that do not have the expected shape.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Strictness {
+    /// Skip what cannot be understood, as long as the puzzle can still be solved.
+    #[default]
+    Lenient,
+    /// Report every malformed line as an error.
+    Strict,
+}
+
+impl Strictness {
+    /// Reports `error` in strict mode and lets the caller skip the line otherwise.
+    pub fn check(self, error: ParseError) -> Result<()> {
+        match self {
+            Strictness::Lenient => Ok(()),
+            Strictness::Strict => Err(error.into()),
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{Result, Strictness};
+use crate::input::{InputSource, DEFAULT_INPUT_DIR, DEFAULT_YEAR};
+use crate::memory::{self, AllocStats};
+use crate::options::Options;
+use crate::solution::{Answer, Part, Runner};
+
+/// How running one part of a day ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    /// The input could not be read or parsed.
+    Error(String),
+    /// The solver panicked.
+    Panic(String),
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Error(message) => write!(f, "error: {}", message),
+            Status::Panic(message) => write!(f, "panic: {}", message),
+        }
+    }
+}
+
+/// The result of running one part of a day.
+#[derive(Debug, Clone)]
+pub struct RunResult {
+    pub day: u8,
+    pub part: Part,
+    /// The input set or file the day was run on.
+    pub input: InputSource,
+    pub answer: Option<Answer>,
+    pub status: Status,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+    /// What parsing and solving allocated, if allocations are counted.
+    pub memory: Option<AllocStats>,
+}
+
+impl RunResult {
+    pub fn elapsed(&self) -> Duration {
+        self.parse_time + self.solve_time
+    }
+}
+
+/// Where the inputs are read from and how they are parsed.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub year: u16,
+    pub input: InputSource,
+    pub input_dir: PathBuf,
+    pub strictness: Strictness,
+    /// Day options given as `name=value`.
+    pub options: Vec<(String, String)>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            year: DEFAULT_YEAR,
+            input: InputSource::default(),
+            input_dir: PathBuf::from(DEFAULT_INPUT_DIR),
+            strictness: Strictness::default(),
+            options: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn read_input(&self, day: u8) -> Result<String> {
+        Ok(self.input.read(&self.input_dir, self.year, day)?)
+    }
+
+    /// The file the input of `day` is read from, `None` for stdin.
+    pub fn input_path(&self, day: u8) -> Option<PathBuf> {
+        self.input.path(&self.input_dir, self.year, day)
+    }
+
+    /// The options of a day for `part`, see [`Options::resolve`].
+    pub fn options(&self, runner: &dyn Runner, part: Part) -> Result<Options> {
+        Options::resolve(runner.day(), runner.options(), part, &self.options)
+    }
+}
+
+/// Runs the given parts of several days, each on its own input, on `jobs`
+/// threads and returns the results of each day in the order of `days`.
+pub fn run_days(
+    days: &[(&dyn Runner, InputSource)],
+    parts: &[Part],
+    config: &Config,
+    jobs: usize,
+) -> Vec<Vec<RunResult>> {
+    let run = |(runner, input): &(&dyn Runner, InputSource)| {
+        let config = Config {
+            input: input.clone(),
+            ..config.clone()
+        };
+        run_day(*runner, parts, &config)
+    };
+    if jobs <= 1 {
+        return days.iter().map(run).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let runs = Mutex::new(vec![None; days.len()]);
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(days.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(day) = days.get(index) else {
+                    break;
+                };
+                let results = run(day);
+                runs.lock().unwrap()[index] = Some(results);
+            });
+        }
+    });
+    runs.into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|results| results.expect("every day is run"))
+        .collect()
+}
+
+/// Runs the given parts of a day, turning errors and panics into a [`Status`]
+/// instead of aborting.
+pub fn run_day(runner: &dyn Runner, parts: &[Part], config: &Config) -> Vec<RunResult> {
+    run(runner, parts, config, || config.read_input(runner.day()))
+}
+
+/// Like [`run_day`], but on the given input text instead of the one `config`
+/// selects.
+pub fn run_text(
+    runner: &dyn Runner,
+    parts: &[Part],
+    config: &Config,
+    text: &str,
+) -> Vec<RunResult> {
+    run(runner, parts, config, || Ok(text.to_owned()))
+}
+
+fn run<F>(runner: &dyn Runner, parts: &[Part], config: &Config, read: F) -> Vec<RunResult>
+where
+    F: FnOnce() -> Result<String>,
+{
+    // Reading is not part of parsing, so it is neither timed nor measured.
+    let (parsed, parse_memory, parse_time) = match read() {
+        Ok(input) => {
+            let start = Instant::now();
+            let (parsed, parse_memory) =
+                memory::measure(|| catch_panic(|| runner.parse(&input, config.strictness)));
+            (parsed, parse_memory, start.elapsed())
+        }
+        Err(err) => (Ok(Err(err)), None, Duration::ZERO),
+    };
+
+    let result = |part, answer, status, solve_time, solve_memory: Option<AllocStats>| RunResult {
+        day: runner.day(),
+        part,
+        input: config.input.clone(),
+        answer,
+        status,
+        parse_time,
+        solve_time,
+        memory: parse_memory.map(|parse| parse.then(solve_memory.unwrap_or_default())),
+    };
+
+    let parsed = match parsed {
+        Ok(Ok(parsed)) => parsed,
+        Ok(Err(err)) => {
+            return parts
+                .iter()
+                .map(|&part| {
+                    let status = Status::Error(err.to_string());
+                    result(part, None, status, Duration::ZERO, None)
+                })
+                .collect();
+        }
+        Err(message) => {
+            return parts
+                .iter()
+                .map(|&part| {
+                    let status = Status::Panic(message.clone());
+                    result(part, None, status, Duration::ZERO, None)
+                })
+                .collect();
+        }
+    };
+
+    parts
+        .iter()
+        .map(|&part| {
+            let options = match config.options(runner, part) {
+                Ok(options) => options,
+                Err(err) => {
+                    let status = Status::Error(err.to_string());
+                    return result(part, None, status, Duration::ZERO, None);
+                }
+            };
+
+            let start = Instant::now();
+            let (answer, solve_memory) =
+                memory::measure(|| catch_panic(|| parsed.solve(part, &options)));
+            let solve_time = start.elapsed();
+            match answer {
+                Ok(answer) => result(part, Some(answer), Status::Ok, solve_time, solve_memory),
+                Err(message) => {
+                    result(part, None, Status::Panic(message), solve_time, solve_memory)
+                }
+            }
+        })
+        .collect()
+}
+
+/// Runs `f`, returning the panic message if it panics.
+pub fn catch_panic<T, F: FnOnce() -> T>(f: F) -> std::result::Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(panic_message)
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".into()
+    }
+}
+
+/// Runs `f` without printing panic messages to stderr; panics are reported
+/// through [`Status::Panic`] instead.
+pub fn with_silent_panics<T, F: FnOnce() -> T>(f: F) -> T {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let res = f();
+    panic::set_hook(hook);
+    res
+}
@@ -0,0 +1,350 @@
+//! A C interface to the solvers, exported by the `cdylib` and `staticlib` builds
+//! of the crate.
+//!
+//! `ffi/advent_of_code.h` declares it and is maintained by hand; the test of
+//! this module checks that it declares every export below. `ffi/test.c` shows
+//! how to use it.
+
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use std::slice;
+
+use crate::error::Strictness;
+use crate::execute::{self, Config, Status};
+use crate::input::InputSource;
+use crate::options;
+use crate::registry;
+use crate::solution::Part;
+
+/// The part was solved.
+pub const AOC_OK: i32 = 0;
+/// The day, part, options or input are invalid.
+pub const AOC_ERROR: i32 = 1;
+/// The solver panicked.
+pub const AOC_PANIC: i32 = 2;
+
+/// The outcome of `aoc_run`.
+pub struct AocResult {
+    status: i32,
+    answer: Option<CString>,
+    error: Option<CString>,
+    time_ns: u64,
+}
+
+impl AocResult {
+    fn error(status: i32, message: &str) -> Self {
+        AocResult {
+            status,
+            answer: None,
+            error: Some(c_string(message)),
+            time_ns: 0,
+        }
+    }
+}
+
+/// `s` as a C string, without any NUL characters it contains.
+fn c_string(s: &str) -> CString {
+    CString::new(s.replace('\0', "")).expect("NUL characters are removed")
+}
+
+/// Writes up to `capacity` days implemented for `year` to `days`, in order, and
+/// returns how many there are in total.
+///
+/// # Safety
+///
+/// `days` must point to `capacity` writable bytes, or be NULL if `capacity` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_days(year: u16, days: *mut u8, capacity: usize) -> usize {
+    let mut count = 0;
+    for runner in registry::days(year) {
+        if count < capacity {
+            *days.add(count) = runner.day();
+        }
+        count += 1;
+    }
+    count
+}
+
+/// The title of a day, or NULL if it is not implemented. Free it with
+/// `aoc_string_free`.
+#[no_mangle]
+pub extern "C" fn aoc_day_title(year: u16, day: u8) -> *mut c_char {
+    match registry::get(year, day) {
+        Some(runner) => c_string(runner.title()).into_raw(),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Frees a string returned by this library. Does nothing for NULL.
+///
+/// # Safety
+///
+/// `s` must be NULL or a string returned by this library that was not freed
+/// yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Solves `part` of a day of `year` on the `input_len` bytes at `input`.
+/// `options` holds the day options as `name=value` separated by whitespace, or
+/// is NULL for none; `strict` rejects malformed input lines. Never returns
+/// NULL; free the result with `aoc_result_free`.
+///
+/// # Safety
+///
+/// `input` must point to `input_len` readable bytes, or be NULL if `input_len`
+/// is 0. `options` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_run(
+    year: u16,
+    day: u8,
+    part: u8,
+    input: *const u8,
+    input_len: usize,
+    options: *const c_char,
+    strict: bool,
+) -> *mut AocResult {
+    let input = match input_len {
+        0 => &[][..],
+        len => slice::from_raw_parts(input, len),
+    };
+    let options = match options.is_null() {
+        true => None,
+        false => Some(CStr::from_ptr(options)),
+    };
+    let result = execute::catch_panic(|| run(year, day, part, input, options, strict))
+        .unwrap_or_else(|message| AocResult::error(AOC_PANIC, &message));
+    Box::into_raw(Box::new(result))
+}
+
+fn run(
+    year: u16,
+    day: u8,
+    part: u8,
+    input: &[u8],
+    options: Option<&CStr>,
+    strict: bool,
+) -> AocResult {
+    let Some(runner) = registry::get(year, day) else {
+        return AocResult::error(
+            AOC_ERROR,
+            &format!(
+                "Day {} of {} not implemented. Available days: {}",
+                day,
+                year,
+                registry::available_days(year)
+            ),
+        );
+    };
+    let part = match part {
+        1 => Part::One,
+        2 => Part::Two,
+        _ => return AocResult::error(AOC_ERROR, &format!("Unknown part {}", part)),
+    };
+    let Ok(text) = std::str::from_utf8(input) else {
+        return AocResult::error(AOC_ERROR, "The puzzle input is not UTF-8");
+    };
+    let Ok(options) = options.map_or(Ok(""), CStr::to_str) else {
+        return AocResult::error(AOC_ERROR, "The options are not UTF-8");
+    };
+
+    let mut config = Config {
+        year,
+        input: InputSource::Stdin,
+        strictness: match strict {
+            true => Strictness::Strict,
+            false => Strictness::Lenient,
+        },
+        ..Config::default()
+    };
+    for option in options.split_whitespace() {
+        match options::split_assignment(option) {
+            Some(option) => config.options.push(option),
+            None => {
+                return AocResult::error(
+                    AOC_ERROR,
+                    &format!("Invalid option {}, expected name=value", option),
+                )
+            }
+        }
+    }
+    if let Err(err) = config.options(runner, part) {
+        return AocResult::error(AOC_ERROR, &err.to_string());
+    }
+
+    let results = execute::run_text(runner, &[part], &config, text);
+    let result = &results[0];
+    let (status, error) = match &result.status {
+        Status::Ok => (AOC_OK, None),
+        Status::Error(message) => (AOC_ERROR, Some(c_string(message))),
+        Status::Panic(message) => (AOC_PANIC, Some(c_string(message))),
+    };
+    AocResult {
+        status,
+        answer: result
+            .answer
+            .as_ref()
+            .map(|answer| c_string(&answer.to_string())),
+        error,
+        time_ns: result.elapsed().as_nanos() as u64,
+    }
+}
+
+/// `AOC_OK`, `AOC_ERROR` or `AOC_PANIC`.
+///
+/// # Safety
+///
+/// `result` must be a result of `aoc_run` that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_result_status(result: *const AocResult) -> i32 {
+    (*result).status
+}
+
+/// The answer, or NULL if the part failed. Valid until the result is freed.
+///
+/// # Safety
+///
+/// `result` must be a result of `aoc_run` that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_result_answer(result: *const AocResult) -> *const c_char {
+    (*result)
+        .answer
+        .as_ref()
+        .map_or(ptr::null(), |s| s.as_ptr())
+}
+
+/// Why the part failed, or NULL if it was solved. Valid until the result is
+/// freed.
+///
+/// # Safety
+///
+/// `result` must be a result of `aoc_run` that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_result_error(result: *const AocResult) -> *const c_char {
+    (*result).error.as_ref().map_or(ptr::null(), |s| s.as_ptr())
+}
+
+/// How long parsing and solving took, in nanoseconds.
+///
+/// # Safety
+///
+/// `result` must be a result of `aoc_run` that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_result_time_ns(result: *const AocResult) -> u64 {
+    (*result).time_ns
+}
+
+/// Frees a result of `aoc_run`. Does nothing for NULL.
+///
+/// # Safety
+///
+/// `result` must be NULL or a result of `aoc_run` that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_result_free(result: *mut AocResult) {
+    if !result.is_null() {
+        drop(Box::from_raw(result));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_should_declare_every_export() {
+        let source = include_str!("ffi.rs");
+        let header = include_str!("../ffi/advent_of_code.h");
+        let declarations = source
+            .lines()
+            .take_while(|line| *line != "#[cfg(test)]")
+            .filter_map(|line| {
+                let item = line.strip_prefix("pub ")?;
+                let item = item.strip_prefix("unsafe ").unwrap_or(item);
+                let name = |rest: &str| {
+                    rest.split(|c: char| !c.is_alphanumeric() && c != '_')
+                        .next()
+                        .unwrap_or_default()
+                        .to_owned()
+                };
+                if let Some(rest) = item.strip_prefix("extern \"C\" fn ") {
+                    Some(format!("{}(", name(rest)))
+                } else if let Some(rest) = item.strip_prefix("const ") {
+                    Some(format!("#define {} ", name(rest)))
+                } else {
+                    let rest = item.strip_prefix("struct ")?;
+                    Some(format!("typedef struct {} ", name(rest)))
+                }
+            });
+
+        for declaration in declarations {
+            assert!(
+                header.contains(&declaration),
+                "ffi/advent_of_code.h lacks `{}`",
+                declaration
+            );
+        }
+    }
+
+    fn run_str(
+        day: u8,
+        part: u8,
+        input: &str,
+        options: &CStr,
+    ) -> (i32, Option<String>, Option<String>) {
+        unsafe {
+            let result = aoc_run(
+                2022,
+                day,
+                part,
+                input.as_ptr(),
+                input.len(),
+                options.as_ptr(),
+                false,
+            );
+            let text = |s: *const c_char| {
+                (!s.is_null()).then(|| CStr::from_ptr(s).to_string_lossy().into_owned())
+            };
+            let outcome = (
+                aoc_result_status(result),
+                text(aoc_result_answer(result)),
+                text(aoc_result_error(result)),
+            );
+            aoc_result_free(result);
+            outcome
+        }
+    }
+
+    #[test]
+    fn run_should_solve_or_explain_the_error() {
+        assert_eq!(
+            (AOC_OK, Some("13".into()), None),
+            run_str(9, 2, "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2\n", c"knots=2")
+        );
+
+        let (status, answer, error) = run_str(9, 2, "R 4\n", c"knots=x");
+        assert_eq!((AOC_ERROR, None), (status, answer));
+        assert!(error.unwrap().contains("knots"));
+
+        let (status, _, error) = run_str(42, 1, "", c"");
+        assert_eq!(AOC_ERROR, status);
+        assert!(error.unwrap().starts_with("Day 42 of 2022 not implemented"));
+    }
+
+    #[test]
+    fn days_should_list_the_implemented_days() {
+        let mut days = [0u8; 4];
+        let count = unsafe { aoc_days(2022, days.as_mut_ptr(), days.len()) };
+
+        assert_eq!(11, count);
+        assert_eq!([1, 2, 3, 4], days);
+        unsafe {
+            let title = aoc_day_title(2022, 4);
+            assert_eq!(c"Camp Cleanup", CStr::from_ptr(title));
+            aoc_string_free(title);
+        }
+        assert!(aoc_day_title(1999, 1).is_null());
+    }
+}
@@ -0,0 +1,114 @@
+//! Random puzzle inputs for stress tests.
+//!
+//! Each day can generate valid inputs of a configurable size, see
+//! [`Solution::generate`](crate::Solution::generate). The same seed and options
+//! always produce the same input.
+
+use std::ops::RangeInclusive;
+
+/// Seed used when none is given.
+pub const DEFAULT_SEED: u64 = 2022;
+
+/// A small, fast pseudo random number generator (SplitMix64). Not suitable for
+/// anything but test data.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range {}..={}", start, end);
+        match (end - start).checked_add(1) {
+            Some(len) => start + self.next_u64() % len,
+            None => self.next_u64(),
+        }
+    }
+
+    /// An index below `len`, which must not be zero.
+    pub fn below(&mut self, len: usize) -> usize {
+        self.range(0..=len as u64 - 1) as usize
+    }
+
+    /// Whether an event with the given probability happens.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        let fraction = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        fraction < probability
+    }
+
+    /// A random element of `items`, which must not be empty.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Strictness;
+    use crate::options::Options;
+    use crate::registry;
+    use crate::solution::Part;
+
+    #[test]
+    fn rng_should_be_reproducible_and_stay_in_range() {
+        let mut first = Rng::new(7);
+        let mut second = Rng::new(7);
+
+        for _ in 0..1000 {
+            let n = first.range(3..=5);
+            assert_eq!(n, second.range(3..=5));
+            assert!((3..=5).contains(&n));
+        }
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn generated_inputs_should_be_valid_and_solvable() {
+        for runner in registry::years().flat_map(registry::days) {
+            let options = Options::defaults(runner.generator_options(), Part::One);
+            let Some(input) = runner.generate(&mut Rng::new(DEFAULT_SEED), &options) else {
+                continue;
+            };
+            let again = runner.generate(&mut Rng::new(DEFAULT_SEED), &options);
+            assert_eq!(Some(&input), again.as_ref(), "day {:02}", runner.day());
+
+            assert_eq!(
+                Vec::<String>::new(),
+                runner
+                    .check(&input)
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+                "day {:02}",
+                runner.day()
+            );
+            let parsed = runner
+                .parse(&input, Strictness::Strict)
+                .unwrap_or_else(|err| panic!("{}", err));
+            for part in Part::ALL {
+                parsed.solve(part, &Options::defaults(runner.options(), part));
+            }
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Sub};
+
+/// A position on a grid or in the plane. `x` grows to the right and `y`
+/// downwards, like lines of text on a screen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: i32, y: i32) -> Self {
+        Point { x, y }
+    }
+
+    /// The neighbouring point in `direction`.
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.offset()
+    }
+
+    /// The four points sharing an edge with this one.
+    pub fn neighbours4(self) -> impl Iterator<Item = Point> {
+        Direction::ALL
+            .into_iter()
+            .map(move |direction| self.step(direction))
+    }
+
+    /// The eight points sharing an edge or a corner with this one.
+    pub fn neighbours8(self) -> impl Iterator<Item = Point> {
+        (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| Point::new(x, y)))
+            .filter(|&offset| offset != Point::ORIGIN)
+            .map(move |offset| self + offset)
+    }
+
+    /// The largest distance along one axis, i.e. the number of king moves
+    /// between the points.
+    pub fn chebyshev_distance(self, other: Point) -> i32 {
+        let difference = other - self;
+        difference.x.abs().max(difference.y.abs())
+    }
+
+    /// The point whose coordinates are the signs of the coordinates of this one.
+    pub fn signum(self) -> Self {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// One of the four directions on a screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// The offset of one step in this direction.
+    pub fn offset(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+            Direction::Right => Point::new(1, 0),
+        }
+    }
+}
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parses a grid with one line per row and one character per cell.
+    ///
+    /// Returns the 1-based line and column of the first character `cell` does
+    /// not accept, or where a line is shorter or longer than the first one.
+    /// An empty text has no rows and is rejected as well.
+    pub fn parse<F>(text: &str, mut cell: F) -> Result<Self, (usize, usize)>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for (index, line) in text.lines().enumerate() {
+            let mut length = 0;
+            for c in line.chars() {
+                if width == Some(length) {
+                    return Err((index + 1, length + 1));
+                }
+                cells.push(cell(c).ok_or((index + 1, length + 1))?);
+                length += 1;
+            }
+            match width {
+                None if length == 0 => return Err((index + 1, 1)),
+                None => width = Some(length),
+                Some(width) if width != length => return Err((index + 1, length + 1)),
+                Some(_) => (),
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) => Ok(Grid {
+                width,
+                height,
+                cells,
+            }),
+            None => Err((1, 1)),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.index_of(point).is_some()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|index| &mut self.cells[index])
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        let x = usize::try_from(point.x).ok().filter(|&x| x < self.width)?;
+        let y = usize::try_from(point.y).ok().filter(|&y| y < self.height)?;
+        Some(y * self.width + x)
+    }
+
+    /// Every point of the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// Every cell of the grid, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        self.cells[y * self.width..(y + 1) * self.width].iter()
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    /// The points from `start` in `direction` up to the edge, without `start`.
+    pub fn ray(&self, start: Point, direction: Direction) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(Some(start.step(direction)), move |point| {
+            Some(point.step(direction))
+        })
+        .take_while(move |&point| self.contains(point))
+    }
+
+    /// The neighbours of `point` sharing an edge with it that are on the grid.
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point
+            .neighbours4()
+            .filter(move |&neighbour| self.contains(neighbour))
+    }
+
+    /// The neighbours of `point` sharing an edge or a corner with it that are on
+    /// the grid.
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point
+            .neighbours8()
+            .filter(move |&neighbour| self.contains(neighbour))
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Draws the grid with one character per cell and a newline after each row.
+    pub fn render<F: FnMut(&T) -> char>(&self, mut cell: F) -> String {
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+        for y in 0..self.height {
+            text.extend(self.row(y).map(&mut cell));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    /// Panics if `point` is outside of the grid.
+    fn index(&self, point: Point) -> &T {
+        match self.get(point) {
+            Some(cell) => cell,
+            None => panic!(
+                "{} is outside of the {}x{} grid",
+                point, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    /// Panics if `point` is outside of the grid.
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(point) {
+            Some(cell) => cell,
+            None => panic!("{} is outside of the {}x{} grid", point, width, height),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    fn digits(text: &str) -> Result<Grid<u32>, (usize, usize)> {
+        Grid::parse(text, |c| c.to_digit(10))
+    }
+
+    #[test]
+    fn parse_should_read_rows_of_cells() {
+        let grid = digits("123\n456\n").unwrap();
+
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(6, grid[Point::new(2, 1)]);
+        assert_eq!(None, grid.get(Point::new(3, 0)));
+        assert_eq!(None, grid.get(Point::new(0, -1)));
+        assert_eq!(vec![&2, &5], grid.column(1).collect_vec());
+        assert_eq!(vec![&4, &5, &6], grid.row(1).collect_vec());
+    }
+
+    #[test]
+    fn parse_should_report_position_of_problem() {
+        assert_eq!(Err((2, 2)), digits("123\n4x6\n"));
+        assert_eq!(Err((2, 3)), digits("123\n45\n"));
+        assert_eq!(Err((2, 4)), digits("123\n4567\n"));
+        assert_eq!(Err((1, 1)), digits(""));
+    }
+
+    #[test]
+    fn ray_should_stop_at_edge() {
+        let grid = digits("123\n456\n789\n").unwrap();
+
+        let ray = grid.ray(Point::new(1, 2), Direction::Up);
+
+        assert_eq!(vec![5, 2], ray.map(|point| grid[point]).collect_vec());
+    }
+
+    #[test]
+    fn neighbours_should_stay_on_grid() {
+        let grid = Grid::new(3, 3, ());
+
+        assert_eq!(2, grid.neighbours4(Point::ORIGIN).count());
+        assert_eq!(3, grid.neighbours8(Point::ORIGIN).count());
+        assert_eq!(8, grid.neighbours8(Point::new(1, 1)).count());
+    }
+
+    #[test]
+    fn render_should_draw_rows() {
+        let grid = digits("12\n34\n").unwrap();
+
+        assert_eq!("..\n.#\n", grid.render(|&n| if n == 4 { '#' } else { '.' }));
+    }
+}
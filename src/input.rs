@@ -0,0 +1,122 @@
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Directory holding the input sets when none is given explicitly.
+pub const DEFAULT_INPUT_DIR: &str = "input";
+
+/// Name of the input set used when nothing else is selected.
+pub const DEFAULT_INPUT_SET: &str = "default";
+
+/// Year whose inputs are used when none is given explicitly.
+pub const DEFAULT_YEAR: u16 = 2022;
+
+/// Where the puzzle input of a day is read from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InputSource {
+    /// An explicit file.
+    Path(PathBuf),
+    /// The standard input.
+    Stdin,
+    /// A named input set inside the input directory, see [`InputSource::path`].
+    Named(String),
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        InputSource::Named(DEFAULT_INPUT_SET.into())
+    }
+}
+
+impl From<&str> for InputSource {
+    /// `-` stands for the standard input, anything else is a path.
+    fn from(arg: &str) -> Self {
+        match arg {
+            "-" => InputSource::Stdin,
+            path => InputSource::Path(path.into()),
+        }
+    }
+}
+
+impl InputSource {
+    /// The file backing this source for the given year and day, if any.
+    ///
+    /// Named input sets, e.g. one per account or `example`, live in
+    /// `<input_dir>/<year>/dayNN/<name>.txt`.
+    pub fn path(&self, input_dir: &Path, year: u16, day: u8) -> Option<PathBuf> {
+        match self {
+            InputSource::Path(path) => Some(path.clone()),
+            InputSource::Stdin => None,
+            InputSource::Named(name) => {
+                Some(day_dir(input_dir, year, day).join(format!("{}.txt", name)))
+            }
+        }
+    }
+
+    pub fn read(&self, input_dir: &Path, year: u16, day: u8) -> std::io::Result<String> {
+        match self.path(input_dir, year, day) {
+            Some(path) => fs::read_to_string(&path).map_err(|err| {
+                std::io::Error::new(err.kind(), format!("{}: {}", path.display(), err))
+            }),
+            None => std::io::read_to_string(std::io::stdin()),
+        }
+    }
+}
+
+/// The directory holding the input sets of a year.
+pub fn year_dir(input_dir: &Path, year: u16) -> PathBuf {
+    input_dir.join(year.to_string())
+}
+
+fn day_dir(input_dir: &Path, year: u16, day: u8) -> PathBuf {
+    year_dir(input_dir, year).join(format!("day{:02}", day))
+}
+
+/// Every input set of a day found in the input directory, the default set
+/// first and the others by name.
+pub fn sets(input_dir: &Path, year: u16, day: u8) -> std::io::Result<Vec<InputSource>> {
+    let entries = match fs::read_dir(day_dir(input_dir, year, day)) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut names = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "txt") {
+            if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+                names.push(name.to_owned());
+            }
+        }
+    }
+    names.sort_by_key(|name| (name != DEFAULT_INPUT_SET, name.clone()));
+    Ok(names.into_iter().map(InputSource::Named).collect())
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSource::Path(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => write!(f, "-"),
+            InputSource::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sets_should_list_default_first() {
+        let input_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("input");
+
+        let sets = sets(&input_dir, DEFAULT_YEAR, 9).unwrap();
+
+        let names: Vec<_> = sets.iter().map(ToString::to_string).collect();
+        assert_eq!(vec!["default", "example", "example2"], names);
+        assert!(super::sets(&input_dir, 1999, 9).unwrap().is_empty());
+    }
+}
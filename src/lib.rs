@@ -0,0 +1,51 @@
+//! Solutions to Advent of Code 2022.
+//!
+//! Every day implements [`Solution`], so it can be used without the binary:
+//!
+//! ```
+//! use advent_of_code_rs::day04::Day04;
+//! use advent_of_code_rs::{Answer, Options, Solution};
+//!
+//! let input = Day04::parse("2-4,6-8\n2-8,3-7\n").unwrap();
+//! assert_eq!(Answer::Number(1), Day04::part1(&input, &Options::default()));
+//! ```
+
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod error;
+pub mod execute;
+pub mod ffi;
+pub mod generate;
+pub mod grid;
+pub mod input;
+pub mod memory;
+pub mod options;
+pub mod parse;
+pub mod registry;
+pub mod repl;
+pub mod report;
+pub mod scaffold;
+pub mod serve;
+pub mod simulation;
+pub mod solution;
+#[cfg(test)]
+mod testing;
+pub mod trace;
+pub mod visualize;
+pub mod watch;
+
+pub use error::{Error, ParseError, Result, Strictness};
+pub use options::Options;
+pub use solution::{Answer, Part, Solution};
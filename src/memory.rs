@@ -0,0 +1,185 @@
+//! Counting of heap allocations, to compare how much memory the days use.
+//!
+//! [`CountingAllocator`] has to be the global allocator of the program, as it is
+//! in the binary built with the `memory` feature. It counts nothing until [`enable`] is called, so that runs
+//! without `--alloc` only pay for checking a flag.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// What the current thread allocated so far.
+#[derive(Debug, Clone, Copy, Default)]
+struct Counters {
+    allocations: u64,
+    bytes: u64,
+    /// Bytes allocated and not yet freed by this thread. Memory freed by another
+    /// thread than the one allocating it can make this negative.
+    live: i64,
+    peak: i64,
+}
+
+thread_local! {
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters {
+            allocations: 0,
+            bytes: 0,
+            live: 0,
+            peak: 0,
+        })
+    };
+}
+
+/// The allocations of a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Number of allocations, including reallocations.
+    pub allocations: u64,
+    /// Bytes allocated in total.
+    pub bytes: u64,
+    /// The most bytes allocated by the run and alive at the same time.
+    pub peak: u64,
+}
+
+impl AllocStats {
+    /// The allocations of this run followed by `next`.
+    pub fn then(self, next: AllocStats) -> AllocStats {
+        AllocStats {
+            allocations: self.allocations + next.allocations,
+            bytes: self.bytes + next.bytes,
+            peak: self.peak.max(next.peak),
+        }
+    }
+}
+
+/// The system allocator, counting the allocations of each thread while
+/// counting is enabled.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn record(allocated: usize, freed: usize) {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+        // Ignores allocations made while the thread is shutting down.
+        let _ = COUNTERS.try_with(|counters| {
+            let mut current = counters.get();
+            if allocated > 0 {
+                current.allocations += 1;
+                current.bytes += allocated as u64;
+            }
+            current.live += allocated as i64 - freed as i64;
+            current.peak = current.peak.max(current.live);
+            counters.set(current);
+        });
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::record(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::record(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// Starts counting allocations. Returns `false`, and counts nothing, if
+/// [`CountingAllocator`] is not the global allocator.
+pub fn enable() -> bool {
+    ENABLED.store(true, Ordering::Relaxed);
+    let before = COUNTERS.with(Cell::get).allocations;
+    drop(std::hint::black_box(Box::new(0u64)));
+    let installed = COUNTERS.with(Cell::get).allocations > before;
+    ENABLED.store(installed, Ordering::Relaxed);
+    installed
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Runs `f` and returns what it allocated on this thread, or `None` if counting
+/// is not enabled.
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Option<AllocStats>) {
+    if !is_enabled() {
+        return (f(), None);
+    }
+
+    let start = COUNTERS.with(|counters| {
+        let start = counters.get();
+        counters.set(Counters {
+            peak: start.live,
+            ..start
+        });
+        start
+    });
+    let result = f();
+    let end = COUNTERS.with(|counters| {
+        let end = counters.get();
+        counters.set(Counters {
+            peak: start.peak.max(end.peak),
+            ..end
+        });
+        end
+    });
+
+    let stats = AllocStats {
+        allocations: end.allocations - start.allocations,
+        bytes: end.bytes - start.bytes,
+        peak: (end.peak - start.live).max(0) as u64,
+    };
+    (result, Some(stats))
+}
+
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measure_should_count_allocations_of_this_thread() {
+        assert!(enable());
+
+        let (_, stats) = measure(|| {
+            let mut kept = Vec::<u8>::with_capacity(100);
+            drop(vec![0u8; 1000]);
+            kept.push(1);
+            std::thread::spawn(|| vec![0u8; 5000]).join().unwrap()
+        });
+
+        let stats = stats.unwrap();
+        // The thread handle allocates as well, the other thread's vector does not
+        // count.
+        assert!(stats.allocations >= 2, "{:?}", stats);
+        assert!((1100..5000).contains(&stats.bytes), "{:?}", stats);
+        assert!((1000..5000).contains(&stats.peak), "{:?}", stats);
+    }
+}
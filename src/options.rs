@@ -0,0 +1,268 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+use crate::error::{Error, Result};
+use crate::solution::Part;
+
+/// The kind of values an option accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionKind {
+    /// A whole number of at least `min`.
+    Integer { min: i64 },
+    /// A whole number from `min` to `max`.
+    Range { min: i64, max: i64 },
+    /// One of the listed words.
+    Choice(&'static [&'static str]),
+}
+
+/// A named option of a day, given on the command line as `name=value`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionSpec {
+    pub name: &'static str,
+    pub kind: OptionKind,
+    /// The value used for part 1 and part 2 if the option is not given.
+    pub defaults: [&'static str; 2],
+    pub help: &'static str,
+}
+
+/// A validated option value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    Integer(i64),
+    Choice(&'static str),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Integer(n) => write!(f, "{}", n),
+            Value::Choice(choice) => write!(f, "{}", choice),
+        }
+    }
+}
+
+impl OptionSpec {
+    pub fn parse(&self, value: &str) -> std::result::Result<Value, String> {
+        match self.kind {
+            OptionKind::Integer { min } => match value.parse::<i64>() {
+                Ok(n) if n >= min => Ok(Value::Integer(n)),
+                _ => Err(format!("an integer of at least {}", min)),
+            },
+            OptionKind::Range { min, max } => match value.parse::<i64>() {
+                Ok(n) if (min..=max).contains(&n) => Ok(Value::Integer(n)),
+                _ => Err(format!("an integer from {} to {}", min, max)),
+            },
+            OptionKind::Choice(choices) => choices
+                .iter()
+                .find(|&&choice| choice == value)
+                .map(|&choice| Value::Choice(choice))
+                .ok_or_else(|| format!("one of {}", choices.join(", "))),
+        }
+    }
+
+    fn default_for(&self, part: Part) -> &'static str {
+        self.defaults[part.number() as usize - 1]
+    }
+}
+
+/// The options of one day and part: the given values, falling back to the
+/// defaults of the day.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options {
+    values: BTreeMap<&'static str, Value>,
+}
+
+impl Options {
+    /// The default options of a day for `part`.
+    pub fn defaults(specs: &[OptionSpec], part: Part) -> Self {
+        Self::resolve(0, specs, part, &[]).expect("defaults of a day should be valid")
+    }
+
+    /// Checks the `given` name and value pairs against the options of `day` and
+    /// fills in the defaults for `part`.
+    pub fn resolve(
+        day: u8,
+        specs: &[OptionSpec],
+        part: Part,
+        given: &[(String, String)],
+    ) -> Result<Self> {
+        for (name, _) in given {
+            if !specs.iter().any(|spec| spec.name == name) {
+                return Err(Error::InvalidOption(format!(
+                    "Day {:02} has no option {}{}",
+                    day,
+                    name,
+                    match specs.len() {
+                        0 => String::new(),
+                        _ => format!(", expected one of {}", names(specs)),
+                    }
+                )));
+            }
+        }
+
+        let mut values = BTreeMap::new();
+        for spec in specs {
+            let value = given
+                .iter()
+                .rev()
+                .find(|(name, _)| name == spec.name)
+                .map_or(spec.default_for(part), |(_, value)| value.as_str());
+            let value = spec.parse(value).map_err(|expected| {
+                Error::InvalidOption(format!(
+                    "Invalid value {} for option {} of day {:02}, expected {}",
+                    value, spec.name, day, expected
+                ))
+            })?;
+            values.insert(spec.name, value);
+        }
+
+        Ok(Options { values })
+    }
+
+    /// The options whose value differs from the default for `part`, as
+    /// `name=value` in name order.
+    pub fn changed(&self, specs: &[OptionSpec], part: Part) -> Vec<String> {
+        let defaults = Options::defaults(specs, part);
+        self.values
+            .iter()
+            .filter(|&(name, value)| defaults.values.get(name) != Some(value))
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect()
+    }
+
+    /// The value of an integer option.
+    ///
+    /// Panics if the day does not declare `name` as an integer option.
+    pub fn integer(&self, name: &str) -> i64 {
+        match self.values.get(name) {
+            Some(Value::Integer(n)) => *n,
+            _ => panic!("no integer option {}", name),
+        }
+    }
+
+    /// The value of a choice option.
+    ///
+    /// Panics if the day does not declare `name` as a choice option.
+    pub fn choice(&self, name: &str) -> &'static str {
+        match self.values.get(name) {
+            Some(Value::Choice(choice)) => choice,
+            _ => panic!("no choice option {}", name),
+        }
+    }
+}
+
+/// Splits a `name=value` argument.
+pub fn split_assignment(arg: &str) -> Option<(String, String)> {
+    arg.split_once('=')
+        .map(|(name, value)| (name.to_owned(), value.to_owned()))
+}
+
+fn names(specs: &[OptionSpec]) -> String {
+    specs
+        .iter()
+        .map(|spec| spec.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Describes the options of a day for `--help <day>`.
+pub fn help(heading: &str, specs: &[OptionSpec]) -> String {
+    let usages: Vec<_> = specs
+        .iter()
+        .map(|spec| match spec.kind {
+            OptionKind::Integer { .. } | OptionKind::Range { .. } => {
+                format!("{}=<n>", spec.name)
+            }
+            OptionKind::Choice(choices) => format!("{}=<{}>", spec.name, choices.join("|")),
+        })
+        .collect();
+    let width = usages.iter().map(String::len).max().unwrap_or(0);
+
+    let mut help = format!("{}:", heading);
+    for (spec, usage) in specs.iter().zip(&usages) {
+        let default = match spec.defaults {
+            [part1, part2] if part1 == part2 => format!("default: {}", part1),
+            [part1, part2] => format!("default: {} for part 1, {} for part 2", part1, part2),
+        };
+        let _ = write!(
+            help,
+            "\n    {:<width$}  {} [{}]",
+            usage,
+            spec.help,
+            default,
+            width = width
+        );
+    }
+    help
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    const SPECS: &[OptionSpec] = &[
+        OptionSpec {
+            name: "knots",
+            kind: OptionKind::Integer { min: 1 },
+            defaults: ["2", "10"],
+            help: "number of knots",
+        },
+        OptionSpec {
+            name: "width",
+            kind: OptionKind::Range { min: 1, max: 5 },
+            defaults: ["3", "3"],
+            help: "width",
+        },
+        OptionSpec {
+            name: "mode",
+            kind: OptionKind::Choice(&["a", "b"]),
+            defaults: ["a", "a"],
+            help: "mode",
+        },
+    ];
+
+    fn given(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn resolve_should_fill_in_defaults_of_part() {
+        let options = Options::resolve(9, SPECS, Part::Two, &given(&[("mode", "b")])).unwrap();
+
+        assert_eq!(10, options.integer("knots"));
+        assert_eq!("b", options.choice("mode"));
+    }
+
+    #[test]
+    fn resolve_should_reject_invalid_values() {
+        for pairs in [
+            [("knots", "0")],
+            [("knots", "x")],
+            [("width", "0")],
+            [("width", "6")],
+            [("mode", "c")],
+            [("rope", "2")],
+        ] {
+            let result = Options::resolve(9, SPECS, Part::One, &given(&pairs));
+
+            assert!(
+                matches!(result, Err(Error::InvalidOption(_))),
+                "{:?}",
+                pairs
+            );
+        }
+    }
+
+    #[test]
+    fn defaults_of_every_day_should_be_valid() {
+        for runner in registry::years().flat_map(registry::days) {
+            for part in Part::ALL {
+                Options::defaults(runner.options(), part);
+            }
+        }
+    }
+}
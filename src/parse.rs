@@ -0,0 +1,316 @@
+//! A small parser combinator toolkit for the puzzle inputs.
+//!
+//! A [`Parser`] consumes the start of a line and returns the value it read and
+//! the rest of the line. On failure it returns the input left where it stopped
+//! matching, from which [`parse_all`] and [`Line::parse`] compute the column
+//! reported in a [`ParseError`].
+
+use std::str::FromStr;
+
+use crate::error::ParseError;
+
+/// The value read and the rest of the input, or the input left at the position
+/// where parsing failed.
+pub type PResult<'a, T> = std::result::Result<(T, &'a str), &'a str>;
+
+pub trait Parser<'a, T> {
+    fn parse(&self, input: &'a str) -> PResult<'a, T>;
+
+    /// Transforms the value read.
+    fn map<U, F>(self, f: F) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+        F: Fn(T) -> U,
+    {
+        move |input| self.parse(input).map(|(value, rest)| (f(value), rest))
+    }
+
+    /// Transforms the value read, failing at its start if `f` returns `None`.
+    fn try_map<U, F>(self, f: F) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+        F: Fn(T) -> Option<U>,
+    {
+        move |input| {
+            let (value, rest) = self.parse(input)?;
+            f(value).map(|value| (value, rest)).ok_or(input)
+        }
+    }
+
+    /// Runs `next` after this parser and keeps both values.
+    fn and<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, (T, U)>
+    where
+        Self: Sized,
+    {
+        move |input| {
+            let (first, rest) = self.parse(input)?;
+            let (second, rest) = next.parse(rest)?;
+            Ok(((first, second), rest))
+        }
+    }
+
+    /// Runs `next` after this parser and keeps only the value of `next`.
+    fn then<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+    {
+        self.and(next).map(|(_, second)| second)
+    }
+
+    /// Runs `next` after this parser and keeps only the value of this one.
+    fn skip<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, T>
+    where
+        Self: Sized,
+    {
+        self.and(next).map(|(first, _)| first)
+    }
+
+    /// Tries `other` if this parser fails. If both fail, the failure that got
+    /// further is reported.
+    fn or(self, other: impl Parser<'a, T>) -> impl Parser<'a, T>
+    where
+        Self: Sized,
+    {
+        move |input| match self.parse(input) {
+            Ok(result) => Ok(result),
+            Err(first) => other.parse(input).map_err(|second| {
+                if second.len() < first.len() {
+                    second
+                } else {
+                    first
+                }
+            }),
+        }
+    }
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(&'a str) -> PResult<'a, T>,
+{
+    fn parse(&self, input: &'a str) -> PResult<'a, T> {
+        self(input)
+    }
+}
+
+/// Matches `expected` exactly, failing at the first character that differs.
+pub fn literal<'a>(expected: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(expected) {
+        Some(rest) => Ok((&input[..expected.len()], rest)),
+        None => {
+            let common: usize = input
+                .chars()
+                .zip(expected.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a.len_utf8())
+                .sum();
+            Err(&input[common..])
+        }
+    }
+}
+
+/// Reads a decimal number with an optional minus sign.
+pub fn number<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: &'a str| {
+        let sign = usize::from(input.starts_with('-'));
+        let digits = input[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(input.len() - sign);
+        match input[..sign + digits].parse() {
+            Ok(n) if digits > 0 => Ok((n, &input[sign + digits..])),
+            _ => Err(input),
+        }
+    }
+}
+
+/// Reads a single character accepted by `predicate`.
+pub fn satisfy<'a, P: Fn(char) -> bool>(predicate: P) -> impl Parser<'a, char> {
+    move |input: &'a str| match input.chars().next() {
+        Some(c) if predicate(c) => Ok((c, &input[c.len_utf8()..])),
+        _ => Err(input),
+    }
+}
+
+/// Reads one or more characters accepted by `predicate`.
+pub fn take_while1<'a, P: Fn(char) -> bool>(predicate: P) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| {
+        let end = input.find(|c| !predicate(c)).unwrap_or(input.len());
+        match end {
+            0 => Err(input),
+            end => Ok((&input[..end], &input[end..])),
+        }
+    }
+}
+
+/// Matches the first of the given words and returns its value.
+pub fn one_of<'a, T: Copy>(choices: &'static [(&'static str, T)]) -> impl Parser<'a, T> {
+    move |input: &'a str| {
+        choices
+            .iter()
+            .find_map(|&(word, value)| input.strip_prefix(word).map(|rest| (value, rest)))
+            .ok_or(input)
+    }
+}
+
+/// Runs `parser` if it matches, without consuming anything otherwise.
+pub fn opt<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Option<T>> {
+    move |input| match parser.parse(input) {
+        Ok((value, rest)) => Ok((Some(value), rest)),
+        Err(_) => Ok((None, input)),
+    }
+}
+
+/// Reads one or more items separated by `separator`.
+pub fn separated<'a, T>(
+    item: impl Parser<'a, T>,
+    separator: &'static str,
+) -> impl Parser<'a, Vec<T>> {
+    move |input| {
+        let (first, mut rest) = item.parse(input)?;
+        let mut items = vec![first];
+        while let Some(after_separator) = rest.strip_prefix(separator) {
+            let (next, after_item) = item.parse(after_separator)?;
+            items.push(next);
+            rest = after_item;
+        }
+        Ok((items, rest))
+    }
+}
+
+/// Parses all of `text`, or returns the 1-based column where it stops matching.
+pub fn parse_all<'a, T>(text: &'a str, parser: impl Parser<'a, T>) -> Result<T, usize> {
+    let column = |rest: &str| text[..text.len() - rest.len()].chars().count() + 1;
+    match parser.parse(text) {
+        Ok((value, "")) => Ok(value),
+        Ok((_, rest)) | Err(rest) => Err(column(rest)),
+    }
+}
+
+/// A line of the input with its 1-based number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Parses the whole line, or returns the 1-based column where it stops
+    /// matching.
+    pub fn parse<T>(&self, parser: impl Parser<'a, T>) -> Result<T, usize> {
+        parse_all(self.text, parser)
+    }
+
+    /// A parse error at `column` of this line.
+    pub fn error(&self, day: u8, column: usize, expected: &str) -> ParseError {
+        ParseError::new(day, self.number, column, self.text, expected)
+    }
+}
+
+/// The numbered lines of `text`.
+pub fn lines(text: &str) -> impl Iterator<Item = Line<'_>> {
+    text.lines().enumerate().map(|(index, text)| Line {
+        number: index + 1,
+        text,
+    })
+}
+
+/// A block of consecutive non-empty lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section<'a> {
+    pub lines: Vec<Line<'a>>,
+}
+
+impl<'a> Section<'a> {
+    /// The line at `index`, or an empty line just below the section if it is
+    /// shorter, so that a missing line is reported where it should have been.
+    pub fn line(&self, index: usize) -> Line<'a> {
+        match self.lines.get(index) {
+            Some(line) => *line,
+            None => Line {
+                number: self.lines.last().map_or(1, |line| line.number) + index + 1
+                    - self.lines.len(),
+                text: "",
+            },
+        }
+    }
+}
+
+/// Splits `text` into sections separated by one or more empty lines.
+pub fn sections(text: &str) -> Vec<Section<'_>> {
+    let mut sections = Vec::new();
+    let mut current = Vec::new();
+    for line in lines(text) {
+        if line.text.is_empty() {
+            if !current.is_empty() {
+                sections.push(Section {
+                    lines: std::mem::take(&mut current),
+                });
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        sections.push(Section { lines: current });
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_all_should_combine_parsers() {
+        let range = || number::<i32>().skip(literal("-")).and(number::<i32>());
+
+        let pair = parse_all("2-4,-6-8", range().skip(literal(",")).and(range()));
+
+        assert_eq!(Ok(((2, 4), (-6, 8))), pair);
+    }
+
+    #[test]
+    fn parse_all_should_report_column_of_failure() {
+        let parser = || literal("move ").then(separated(number::<u32>(), ", "));
+
+        assert_eq!(Ok(vec![1, 2]), parse_all("move 1, 2", parser()));
+        assert_eq!(Err(5), parse_all("mover 1", parser()));
+        assert_eq!(Err(9), parse_all("move 1, x", parser()));
+        assert_eq!(Err(7), parse_all("move 1 2", parser()));
+        assert_eq!(Err(6), parse_all("move ", parser()));
+    }
+
+    #[test]
+    fn or_should_report_furthest_failure() {
+        let parser = || {
+            literal("noop")
+                .map(|_| None)
+                .or(literal("addx ").then(number::<i32>()).map(Some))
+        };
+
+        assert_eq!(Ok(Some(-3)), parse_all("addx -3", parser()));
+        assert_eq!(Ok(None), parse_all("noop", parser()));
+        assert_eq!(Err(6), parse_all("addx y", parser()));
+    }
+
+    #[test]
+    fn sections_should_be_separated_by_empty_lines() {
+        let sections = sections("a\nb\n\n\nc\n");
+
+        assert_eq!(2, sections.len());
+        assert_eq!(
+            Line {
+                number: 5,
+                text: "c"
+            },
+            sections[1].line(0)
+        );
+        assert_eq!(
+            Line {
+                number: 6,
+                text: ""
+            },
+            sections[1].line(1)
+        );
+    }
+}
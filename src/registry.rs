@@ -0,0 +1,57 @@
+use crate::solution::Runner;
+use crate::*;
+
+/// The implemented days of one event.
+struct Year {
+    year: u16,
+    days: &'static [&'static dyn Runner],
+}
+
+/// Every implemented day, by year and in calendar order. Registering a new day
+/// is one line here.
+static YEARS: &[Year] = &[Year {
+    year: 2022,
+    days: &[
+        &day01::Day01,
+        &day02::Day02,
+        &day03::Day03,
+        &day04::Day04,
+        &day05::Day05,
+        &day06::Day06,
+        &day07::Day07,
+        &day08::Day08,
+        &day09::Day09,
+        &day10::Day10,
+        &day11::Day11,
+    ],
+}];
+
+pub fn years() -> impl Iterator<Item = u16> {
+    YEARS.iter().map(|year| year.year)
+}
+
+/// The days of `year`, none if the year has no solutions.
+pub fn days(year: u16) -> impl Iterator<Item = &'static dyn Runner> {
+    YEARS
+        .iter()
+        .filter(move |entry| entry.year == year)
+        .flat_map(|entry| entry.days.iter().copied())
+}
+
+pub fn get(year: u16, day: u8) -> Option<&'static dyn Runner> {
+    days(year).find(|runner| runner.day() == day)
+}
+
+pub fn available_years() -> String {
+    years()
+        .map(|year| year.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn available_days(year: u16) -> String {
+    days(year)
+        .map(|runner| runner.day().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
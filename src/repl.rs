@@ -0,0 +1,363 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result, Strictness};
+use crate::execute::{self, Config};
+use crate::input::InputSource;
+use crate::options;
+use crate::registry;
+use crate::report::format_duration;
+use crate::solution::{Parsed, Part, Runner};
+
+const HELP: &str = "\
+Commands:
+    run <day> [part] [name=value...]  solve a day, e.g. `run 9 part1 knots=10`
+    set input <path>                  read the input of every day from <path>
+    set set <name>                    use the input set <dir>/<year>/dayNN/<name>.txt
+    set year <year>                   solve the days of another year
+    set input-dir <dir>               directory of the input sets
+    set strict <on|off>               report malformed input lines
+    show                              print the current settings
+    time                              show the timings of the last run
+    history                           list the commands entered so far
+    help                              print this help
+    quit                              leave";
+
+/// Which parsed input can be reused for a run.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    year: u16,
+    day: u8,
+    input: InputSource,
+    input_dir: PathBuf,
+    strictness: Strictness,
+}
+
+struct Cached {
+    /// Hash of the text the input was parsed from, to notice when it changed.
+    text_hash: u64,
+    parsed: Box<dyn Parsed>,
+    parse_time: Duration,
+}
+
+/// Timings of the last `run`.
+struct Timings {
+    day: u8,
+    parse_time: Duration,
+    cached: bool,
+    solve_times: Vec<(Part, Duration)>,
+}
+
+/// An interactive shell keeping parsed inputs between commands.
+pub struct Repl {
+    config: Config,
+    cache: HashMap<CacheKey, Cached>,
+    history: Vec<String>,
+    last_run: Option<Timings>,
+}
+
+impl Repl {
+    pub fn new(config: Config) -> Self {
+        Repl {
+            config,
+            cache: HashMap::new(),
+            history: Vec::new(),
+            last_run: None,
+        }
+    }
+
+    /// Reads commands from `input` until it ends or `quit` is entered. Errors of
+    /// single commands are printed and do not end the session.
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, mut output: W) -> io::Result<()> {
+        writeln!(output, "Type `help` for the list of commands.")?;
+        let mut lines = input.lines();
+        loop {
+            write!(output, "> ")?;
+            output.flush()?;
+            let Some(line) = lines.next().transpose()? else {
+                writeln!(output)?;
+                return Ok(());
+            };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            self.history.push(line.to_owned());
+            if matches!(line, "quit" | "exit") {
+                return Ok(());
+            }
+
+            if let Err(err) = execute::with_silent_panics(|| self.execute(line, &mut output)) {
+                writeln!(output, "error: {}", err)?;
+            }
+        }
+    }
+
+    fn execute<W: Write>(&mut self, line: &str, output: &mut W) -> Result<()> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["run", day, args @ ..] => self.run_day(day, args, output),
+            ["set", "input", path] => {
+                if *path == "-" {
+                    return Err(Error::Usage(
+                        "the shell cannot read inputs from stdin".into(),
+                    ));
+                }
+                self.config.input = InputSource::from(*path);
+                self.show(output)
+            }
+            ["set", "set", name] => {
+                self.config.input = InputSource::Named(name.to_string());
+                self.show(output)
+            }
+            ["set", "year", year] => {
+                self.config.year = year
+                    .parse()
+                    .ok()
+                    .filter(|year| registry::years().any(|known| known == *year))
+                    .ok_or_else(|| {
+                        Error::Usage(format!(
+                            "Unknown year {}. Available years: {}",
+                            year,
+                            registry::available_years()
+                        ))
+                    })?;
+                self.show(output)
+            }
+            ["set", "input-dir", dir] => {
+                self.config.input_dir = dir.into();
+                self.show(output)
+            }
+            ["set", "strict", on_or_off] => {
+                self.config.strictness = match *on_or_off {
+                    "on" => Strictness::Strict,
+                    "off" => Strictness::Lenient,
+                    _ => {
+                        return Err(Error::Usage(
+                            "expected `set strict on` or `set strict off`".into(),
+                        ))
+                    }
+                };
+                self.show(output)
+            }
+            ["show"] => self.show(output),
+            ["time"] => self.time(output),
+            ["history"] => {
+                for (index, command) in self.history.iter().enumerate() {
+                    writeln!(output, "{:4}  {}", index + 1, command)?;
+                }
+                Ok(())
+            }
+            ["help"] => Ok(writeln!(output, "{}", HELP)?),
+            _ => Err(Error::Usage(format!(
+                "unknown command `{}`, type `help` for the list of commands",
+                line
+            ))),
+        }
+    }
+
+    fn run_day<W: Write>(&mut self, day: &str, args: &[&str], output: &mut W) -> Result<()> {
+        let runner = day
+            .parse::<u8>()
+            .ok()
+            .and_then(|day| registry::get(self.config.year, day))
+            .ok_or_else(|| Error::UnknownDay {
+                year: self.config.year,
+                day: day.to_owned(),
+            })?;
+
+        let mut parts = Vec::new();
+        let mut given = Vec::new();
+        for arg in args {
+            if let Some(option) = options::split_assignment(arg) {
+                given.push(option);
+            } else {
+                parts.push(Part::try_from(*arg).map_err(|_| Error::UnknownPart(arg.to_string()))?);
+            }
+        }
+        if parts.is_empty() {
+            parts = Part::ALL.to_vec();
+        }
+        let config = Config {
+            options: given,
+            ..self.config.clone()
+        };
+        let options = parts
+            .iter()
+            .map(|&part| config.options(runner, part))
+            .collect::<Result<Vec<_>>>()?;
+
+        let (cached, parse_time) = self.parse(runner, &config)?;
+        let parsed = &self.cache[&cache_key(runner, &config)].parsed;
+
+        let mut solve_times = Vec::new();
+        for (&part, options) in parts.iter().zip(&options) {
+            let start = Instant::now();
+            let answer =
+                execute::catch_panic(|| parsed.solve(part, options)).map_err(Error::Failed)?;
+            solve_times.push((part, start.elapsed()));
+            writeln!(output, "{}", runner.describe(part, options, &answer))?;
+        }
+
+        self.last_run = Some(Timings {
+            day: runner.day(),
+            parse_time,
+            cached,
+            solve_times,
+        });
+        Ok(())
+    }
+
+    /// Parses the input of a day unless the same text was parsed before, and
+    /// returns whether it was cached and how long parsing took.
+    fn parse(&mut self, runner: &dyn Runner, config: &Config) -> Result<(bool, Duration)> {
+        let text = config.read_input(runner.day())?;
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        let text_hash = hasher.finish();
+
+        let key = cache_key(runner, config);
+        if let Some(cached) = self.cache.get(&key) {
+            if cached.text_hash == text_hash {
+                return Ok((true, cached.parse_time));
+            }
+        }
+
+        let start = Instant::now();
+        let parsed = execute::catch_panic(|| runner.parse(&text, config.strictness))
+            .map_err(Error::Failed)??;
+        let parse_time = start.elapsed();
+        self.cache.insert(
+            key,
+            Cached {
+                text_hash,
+                parsed,
+                parse_time,
+            },
+        );
+        Ok((false, parse_time))
+    }
+
+    fn show<W: Write>(&self, output: &mut W) -> Result<()> {
+        let strict = match self.config.strictness {
+            Strictness::Lenient => "off",
+            Strictness::Strict => "on",
+        };
+        writeln!(
+            output,
+            "year: {}, input: {}, input-dir: {}, strict: {}",
+            self.config.year,
+            self.config.input,
+            self.config.input_dir.display(),
+            strict
+        )?;
+        Ok(())
+    }
+
+    fn time<W: Write>(&self, output: &mut W) -> Result<()> {
+        let Some(timings) = &self.last_run else {
+            writeln!(output, "nothing has been run yet")?;
+            return Ok(());
+        };
+        writeln!(
+            output,
+            "day {:02} parse: {}{}",
+            timings.day,
+            format_duration(timings.parse_time),
+            if timings.cached { " (cached)" } else { "" }
+        )?;
+        for (part, solve_time) in &timings.solve_times {
+            writeln!(
+                output,
+                "day {:02} part{}: {}",
+                timings.day,
+                part,
+                format_duration(*solve_time)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn cache_key(runner: &dyn Runner, config: &Config) -> CacheKey {
+    CacheKey {
+        year: config.year,
+        day: runner.day(),
+        input: config.input.clone(),
+        input_dir: config.input_dir.clone(),
+        strictness: config.strictness,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn session(commands: &str) -> String {
+        let config = Config {
+            input_dir: Path::new(env!("CARGO_MANIFEST_DIR")).join("input"),
+            input: InputSource::Named("example".into()),
+            ..Config::default()
+        };
+        let mut output = Vec::new();
+        Repl::new(config)
+            .run(commands.as_bytes(), &mut output)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn run_should_apply_part_and_options() {
+        let output = session("run 9 part2 knots=2\nrun 1 1 top=2\n");
+
+        assert!(output.contains("Tail was at 13 positions"), "{}", output);
+        assert!(
+            output.contains("The 2 elves with the most calories carry 35000"),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn time_should_report_cached_parse() {
+        let output = session("run 4 1\nrun 4 2\ntime\nhistory\n");
+
+        assert!(output.contains("day 04 parse: "), "{}", output);
+        assert!(output.contains("(cached)"), "{}", output);
+        assert!(output.contains("   2  run 4 2"), "{}", output);
+        assert!(output.contains("   3  time"), "{}", output);
+    }
+
+    #[test]
+    fn errors_should_not_end_session() {
+        let output = session("run 9 knots=0\nrun 42\nset year 1999\nfly\nrun 4 1\n");
+
+        assert!(
+            output.contains("error: Invalid value 0 for option knots"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("error: Day 42 of 2022 not implemented"),
+            "{}",
+            output
+        );
+        assert!(output.contains("error: Unknown year 1999"), "{}", output);
+        assert!(
+            output.contains("error: unknown command `fly`"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("The number of fully contained ranges is 2"),
+            "{}",
+            output
+        );
+    }
+}
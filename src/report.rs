@@ -0,0 +1,232 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::execute::{Config, RunResult, Status};
+use crate::solution::Answer;
+
+/// Prints one row per day, input and part, with the allocations if they were
+/// counted. Answers spanning several lines are printed in full below the table.
+pub fn print_table(results: &[RunResult]) {
+    let with_memory = results.iter().any(|result| result.memory.is_some());
+    let rows: Vec<Vec<String>> = results
+        .iter()
+        .map(|result| {
+            let mut row = vec![
+                format!("{:02}", result.day),
+                result.input.to_string(),
+                result.part.to_string(),
+                answer_cell(result.answer.as_ref()),
+                format_duration(result.elapsed()),
+            ];
+            if with_memory {
+                row.extend(match result.memory {
+                    Some(memory) => [
+                        memory.allocations.to_string(),
+                        format_bytes(memory.bytes),
+                        format_bytes(memory.peak),
+                    ],
+                    None => ["-", "-", "-"].map(String::from),
+                });
+            }
+            row.push(result.status.to_string());
+            row
+        })
+        .collect();
+
+    let mut header = vec!["Day", "Input", "Part", "Answer", "Time"];
+    if with_memory {
+        header.extend(["Allocs", "Allocated", "Peak"]);
+    }
+    header.push("Status");
+    let header: Vec<String> = header.into_iter().map(String::from).collect();
+    let mut widths: Vec<_> = header.iter().map(|cell| cell.len()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    print_row(&header, &widths);
+    println!(
+        "{}",
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-")
+    );
+    for row in &rows {
+        print_row(row, &widths);
+    }
+
+    for result in results {
+        if let Some(Answer::Text(text)) = &result.answer {
+            if text.contains('\n') {
+                println!(
+                    "\nDay {:02} ({}) part {}:\n{}",
+                    result.day, result.input, result.part, text
+                );
+            }
+        }
+    }
+
+    let failed = results
+        .iter()
+        .filter(|result| result.status != Status::Ok)
+        .count();
+    println!(
+        "\n{} of {} parts succeeded",
+        results.len() - failed,
+        results.len()
+    );
+}
+
+fn print_row(row: &[String], widths: &[usize]) {
+    let cells: Vec<_> = row
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect();
+    println!("{}", cells.join(" | ").trim_end());
+}
+
+fn answer_cell(answer: Option<&Answer>) -> String {
+    match answer {
+        Some(Answer::Text(text)) if text.contains('\n') => "(see below)".into(),
+        Some(answer) => answer.to_string(),
+        None => "-".into(),
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros < 1_000 {
+        format!("{} µs", micros)
+    } else if micros < 1_000_000 {
+        format!("{:.2} ms", micros as f64 / 1_000.0)
+    } else {
+        format!("{:.2} s", duration.as_secs_f64())
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes < 1 << 10 {
+        format!("{} B", bytes)
+    } else if bytes < 1 << 20 {
+        format!("{:.2} KiB", bytes as f64 / (1 << 10) as f64)
+    } else {
+        format!("{:.2} MiB", bytes as f64 / (1 << 20) as f64)
+    }
+}
+
+/// Prints one JSON object per line for each day and part, for consumption by
+/// other tools.
+pub fn print_json(results: &[RunResult], config: &Config) {
+    for result in results {
+        println!("{}", to_json(result, config));
+    }
+}
+
+/// Encodes a result as a single-line JSON object. Timings are in nanoseconds;
+/// the input path is `null` when reading from stdin. The allocation counts are
+/// only included if they were measured.
+pub fn to_json(result: &RunResult, config: &Config) -> String {
+    let (answer, answer_type) = match &result.answer {
+        Some(Answer::Number(n)) => (n.to_string(), "number"),
+        Some(Answer::Text(text)) => (json_string(text), "text"),
+        Some(Answer::None) | None => ("null".into(), "none"),
+    };
+    let input = match result
+        .input
+        .path(&config.input_dir, config.year, result.day)
+    {
+        Some(path) => json_string(&path.display().to_string()),
+        None => "null".into(),
+    };
+    let memory = match result.memory {
+        Some(memory) => format!(
+            ",\"allocations\":{},\"allocated_bytes\":{},\"peak_bytes\":{}",
+            memory.allocations, memory.bytes, memory.peak
+        ),
+        None => String::new(),
+    };
+    let (status, error) = match &result.status {
+        Status::Ok => ("ok", "null".into()),
+        Status::Error(message) => ("error", json_string(message)),
+        Status::Panic(message) => ("panic", json_string(message)),
+    };
+
+    format!(
+        "{{\"day\":{},\"part\":{},\"answer\":{},\"answer_type\":\"{}\",\
+         \"parse_time_ns\":{},\"solve_time_ns\":{},\"input\":{},\"status\":\"{}\",\"error\":{}{}}}",
+        result.day,
+        result.part,
+        answer,
+        answer_type,
+        result.parse_time.as_nanos(),
+        result.solve_time.as_nanos(),
+        input,
+        status,
+        error,
+        memory
+    )
+}
+
+/// Quotes and escapes `s` as a JSON string.
+pub fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::InputSource;
+    use crate::solution::Part;
+
+    #[test]
+    fn json_string_should_escape_special_characters() {
+        assert_eq!(r#""a\"b\\c\nd\u0001""#, json_string("a\"b\\c\nd\u{1}"));
+    }
+
+    #[test]
+    fn to_json_should_encode_failed_run() {
+        let result = RunResult {
+            day: 5,
+            part: Part::Two,
+            input: InputSource::Stdin,
+            answer: None,
+            status: Status::Error("bad \"line\"".into()),
+            parse_time: Duration::from_nanos(7),
+            solve_time: Duration::ZERO,
+            memory: None,
+        };
+
+        assert_eq!(
+            r#"{"day":5,"part":2,"answer":null,"answer_type":"none","parse_time_ns":7,"solve_time_ns":0,"input":null,"status":"error","error":"bad \"line\""}"#,
+            to_json(&result, &Config::default())
+        );
+    }
+
+    #[test]
+    fn format_bytes_should_pick_unit() {
+        assert_eq!("1023 B", format_bytes(1023));
+        assert_eq!("1.50 KiB", format_bytes(1536));
+        assert_eq!("3.00 MiB", format_bytes(3 << 20));
+    }
+}
@@ -0,0 +1,385 @@
+//! Generates the files of a new day and registers it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::input::{InputSource, DEFAULT_INPUT_SET};
+
+/// The solver of a new day. `{year}`, `{day}`, `{nn}`, `{title}` and `{example}`
+/// are replaced by the year, the day, the day with two digits, the puzzle title
+/// and the path of the example input.
+const TEMPLATE: &str = r#"use crate::error::{Result, Strictness};
+use crate::options::Options;
+use crate::parse;
+use crate::solution::{Answer, Solution};
+
+pub struct Day{nn};
+
+impl Solution for Day{nn} {
+    const DAY: u8 = {day};
+    const TITLE: &'static str = "{title}";
+
+    /// The lines of the puzzle input.
+    type Input = Vec<String>;
+
+    fn parse_with(input: &str, _strictness: Strictness) -> Result<Self::Input> {
+        Ok(parse::lines(input)
+            .map(|line| line.text.to_owned())
+            .collect())
+    }
+
+    fn part1(_input: &Self::Input, _options: &Options) -> Answer {
+        Answer::None
+    }
+
+    fn part2(_input: &Self::Input, _options: &Options) -> Answer {
+        Answer::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::example_tests;
+
+    // Fill in the answers of the example of the puzzle, pasted into
+    // {example}.
+    example_tests! {
+        Day{nn}, {year};
+        example: part1 = Answer::None, part2 = Answer::None;
+    }
+}
+"#;
+
+/// Creates the solver of `day` in the repository at `root` and empty example and
+/// default inputs in `input_dir`, and adds the day to `src/lib.rs` and the
+/// registry of `year`. Returns the files that were written. Existing input files
+/// are kept.
+pub fn new_day(
+    root: &Path,
+    input_dir: &Path,
+    year: u16,
+    day: u8,
+    title: &str,
+) -> Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        return Err(Error::Usage(format!(
+            "Invalid day {}, expected 1 to 25",
+            day
+        )));
+    }
+    let lib_path = root.join("src").join("lib.rs");
+    let registry_path = root.join("src").join("registry.rs");
+    let solver_path = root.join("src").join(format!("day{:02}.rs", day));
+    if !lib_path.is_file() || !registry_path.is_file() {
+        return Err(Error::Usage(format!(
+            "{} is not the root of the repository, src/lib.rs or src/registry.rs is missing",
+            root.display()
+        )));
+    }
+    if solver_path.exists() {
+        return Err(Error::Usage(format!(
+            "{} already exists",
+            solver_path.display()
+        )));
+    }
+
+    let lib = add_module(&fs::read_to_string(&lib_path)?, day).ok_or_else(|| {
+        Error::Failed(format!("Cannot add day {} to {}", day, lib_path.display()))
+    })?;
+    let registry = register(&fs::read_to_string(&registry_path)?, year, day).ok_or_else(|| {
+        Error::Failed(format!(
+            "Cannot add day {} of {} to {}",
+            day,
+            year,
+            registry_path.display()
+        ))
+    })?;
+
+    let input_path = |set: &str| {
+        InputSource::Named(set.into())
+            .path(input_dir, year, day)
+            .expect("named input sets are files")
+    };
+
+    let mut written = Vec::new();
+    fs::write(
+        &solver_path,
+        solver(year, day, title, &input_path("example")),
+    )?;
+    written.push(solver_path);
+    fs::write(&lib_path, lib)?;
+    written.push(lib_path);
+    fs::write(&registry_path, registry)?;
+    written.push(registry_path);
+
+    for set in [DEFAULT_INPUT_SET, "example"] {
+        let path = input_path(set);
+        if !path.exists() {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&path, "")?;
+            written.push(path);
+        }
+    }
+    Ok(written)
+}
+
+fn solver(year: u16, day: u8, title: &str, example: &Path) -> String {
+    TEMPLATE
+        .replace("{example}", &example.display().to_string())
+        .replace("{year}", &year.to_string())
+        .replace("{nn}", &format!("{:02}", day))
+        .replace("{day}", &day.to_string())
+        .replace("{title}", &title.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Inserts `pub mod dayNN;` among the other days of `lib`, in order. Returns
+/// `None` if the module is already there or there are no days to insert after.
+fn add_module(lib: &str, day: u8) -> Option<String> {
+    let module = format!("pub mod day{:02};", day);
+    if lib.lines().any(|line| line == module) {
+        return None;
+    }
+    insert_sorted(lib, 0..lib.lines().count(), &module, |line| {
+        line.starts_with("pub mod day")
+    })
+}
+
+/// Inserts `&dayNN::DayNN,` into the days of `year` in `registry`, in order,
+/// adding the year if it has no days yet. Returns `None` if the year already
+/// has the day or the years cannot be found.
+fn register(registry: &str, year: u16, day: u8) -> Option<String> {
+    let registry = expand(registry);
+    let lines: Vec<_> = registry.lines().collect();
+    let entry = format!("&day{:02}::Day{:02},", day, day);
+    let Some(start) = lines
+        .iter()
+        .position(|line| line.trim() == format!("year: {},", year))
+    else {
+        return add_year(&registry, year, &entry);
+    };
+    let end = start + lines[start..].iter().position(|line| line.trim() == "],")?;
+
+    if lines[start..end].iter().any(|line| line.trim() == entry) {
+        return None;
+    }
+    insert_sorted(&registry, start..end, &entry, |line| {
+        line.trim().starts_with("&day")
+    })
+}
+
+/// Undoes what rustfmt does to short lists in `registry`: a single year written
+/// as `&[Year {` gets a block of its own, and days on one line get one line
+/// each.
+fn expand(registry: &str) -> String {
+    let mut lines = Vec::new();
+    let mut single_year = false;
+    for line in registry.lines() {
+        if let Some(head) = line.strip_suffix("&[Year {") {
+            lines.push(format!("{}&[", head));
+            lines.push("    Year {".to_owned());
+            single_year = true;
+            continue;
+        }
+        if single_year && line == "}];" {
+            lines.push("    },".to_owned());
+            lines.push("];".to_owned());
+            single_year = false;
+            continue;
+        }
+
+        let own_indent = &line[..line.len() - line.trim_start().len()];
+        let indent = if single_year && !line.is_empty() {
+            format!("    {}", own_indent)
+        } else {
+            own_indent.to_owned()
+        };
+        let days = line
+            .trim_start()
+            .strip_prefix("days: &[")
+            .and_then(|days| days.strip_suffix("],"))
+            .filter(|days| !days.is_empty());
+        match days {
+            Some(days) => {
+                lines.push(format!("{}days: &[", indent));
+                for day in days.split(", ") {
+                    lines.push(format!("{}    {},", indent, day));
+                }
+                lines.push(format!("{}],", indent));
+            }
+            None => lines.push(format!("{}{}", indent, line.trim_start())),
+        }
+    }
+    join(lines, registry)
+}
+
+/// Adds `year` with the single day `entry` to the years of an expanded
+/// `registry`, in order.
+fn add_year(registry: &str, year: u16, entry: &str) -> Option<String> {
+    let mut lines: Vec<String> = registry.lines().map(String::from).collect();
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with("static YEARS"))?;
+    let end = start + lines[start..].iter().position(|line| line == "];")?;
+    let year_of = |index: usize| {
+        let line = lines.get(index + 1)?.trim();
+        line.strip_prefix("year: ")?
+            .strip_suffix(',')?
+            .parse::<u16>()
+            .ok()
+    };
+    let index = (start..end)
+        .find(|&index| lines[index].trim() == "Year {" && year_of(index) > Some(year))
+        .unwrap_or(end);
+
+    let block = [
+        "    Year {".to_owned(),
+        format!("        year: {},", year),
+        "        days: &[".to_owned(),
+        format!("            {}", entry),
+        "        ],".to_owned(),
+        "    },".to_owned(),
+    ];
+    lines.splice(index..index, block);
+    Some(join(lines, registry))
+}
+
+/// Inserts `new` before the first line within `range` accepted by `is_entry`
+/// that sorts after it, or after the last such line, with the same indentation.
+fn insert_sorted<F>(
+    text: &str,
+    range: std::ops::Range<usize>,
+    new: &str,
+    is_entry: F,
+) -> Option<String>
+where
+    F: Fn(&str) -> bool,
+{
+    let mut lines: Vec<String> = text.lines().map(String::from).collect();
+    let entries: Vec<_> = range.filter(|&index| is_entry(&lines[index])).collect();
+    let last = *entries.last()?;
+    let index = entries
+        .iter()
+        .copied()
+        .find(|&index| lines[index].trim() > new)
+        .unwrap_or(last + 1);
+
+    let reference = &lines[entries[0]];
+    let indent = &reference[..reference.len() - reference.trim_start().len()];
+    lines.insert(index, format!("{}{}", indent, new));
+    Some(join(lines, text))
+}
+
+/// Joins `lines`, ending with a newline if `text` did.
+fn join(lines: Vec<String>, text: &str) -> String {
+    let mut result = lines.join("\n");
+    if text.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "\
+pub mod cli;
+pub mod day01;
+pub mod day04;
+pub mod error;
+";
+
+    const REGISTRY: &str = "\
+static YEARS: &[Year] = &[
+    Year {
+        year: 2021,
+        days: &[&day01::Day01],
+    },
+    Year {
+        year: 2022,
+        days: &[
+            &day01::Day01,
+            &day04::Day04,
+        ],
+    },
+];
+";
+
+    #[test]
+    fn add_module_should_keep_days_in_order() {
+        assert!(add_module(LIB, 2)
+            .unwrap()
+            .contains("pub mod day01;\npub mod day02;\npub mod day04;\n"));
+        assert!(add_module(LIB, 12)
+            .unwrap()
+            .ends_with("pub mod day04;\npub mod day12;\npub mod error;\n"));
+        assert_eq!(None, add_module(LIB, 4));
+    }
+
+    #[test]
+    fn register_should_add_day_to_its_year() {
+        let registry = register(REGISTRY, 2022, 12).unwrap();
+
+        assert!(registry.contains(
+            "            &day04::Day04,\n            &day12::Day12,\n        ],\n    },\n];"
+        ));
+        assert_eq!(None, register(REGISTRY, 2022, 4));
+        assert_eq!(None, register(REGISTRY, 2021, 1));
+    }
+
+    #[test]
+    fn register_should_expand_days_on_one_line() {
+        let registry = register(REGISTRY, 2021, 2).unwrap();
+
+        assert!(registry.contains(
+            "        year: 2021,\n        days: &[\n            &day01::Day01,\n            \
+             &day02::Day02,\n        ],\n    },"
+        ));
+    }
+
+    #[test]
+    fn register_should_add_missing_year_in_order() {
+        let registry = register(REGISTRY, 2020, 12).unwrap();
+
+        assert!(registry.starts_with(
+            "static YEARS: &[Year] = &[\n    Year {\n        year: 2020,\n        days: &[\n            \
+             &day12::Day12,\n        ],\n    },\n    Year {\n        year: 2021,"
+        ));
+        assert!(register(REGISTRY, 2023, 12).unwrap().ends_with(
+            "    },\n    Year {\n        year: 2023,\n        days: &[\n            \
+                        &day12::Day12,\n        ],\n    },\n];\n"
+        ));
+    }
+
+    #[test]
+    fn register_should_expand_a_single_year() {
+        let registry =
+            "static YEARS: &[Year] = &[Year {\n    year: 2022,\n    days: &[&day01::Day01],\n}];\n";
+
+        assert_eq!(
+            "static YEARS: &[Year] = &[\n    Year {\n        year: 2022,\n        days: &[\n            \
+             &day01::Day01,\n        ],\n    },\n    Year {\n        year: 2023,\n        days: &[\n            \
+             &day12::Day12,\n        ],\n    },\n];\n",
+            register(registry, 2023, 12).unwrap()
+        );
+    }
+
+    #[test]
+    fn solver_should_fill_in_day_and_title() {
+        let example = InputSource::Named("example".into())
+            .path(Path::new("input"), 2022, 12)
+            .unwrap();
+
+        let solver = solver(2022, 12, "Hill \"Climbing\"", &example);
+
+        assert!(solver.contains("pub struct Day12;"));
+        assert!(solver.contains("Day12, 2022;"));
+        assert!(solver.contains("const DAY: u8 = 12;"));
+        assert!(solver.contains(r#"const TITLE: &'static str = "Hill \"Climbing\"";"#));
+        assert!(solver.contains(&format!("into\n    // {}.", example.display())));
+    }
+}
@@ -0,0 +1,555 @@
+//! A small HTTP server solving puzzle inputs sent to it, for tools that would
+//! rather not start a process per input.
+//!
+//! `POST /solve/<day>[/<part>]?name=value...` solves the request body with the
+//! given day options, `strict` parses it strictly. The response is a JSON object
+//! with the results of each part as printed by `--json` and the problems
+//! `check` finds in the input. `GET /days` lists the days.
+//!
+//! Requests are handled one at a time. Each solve gets a time budget; a day
+//! that exceeds it cannot be stopped, so its thread keeps running in the
+//! background and further solves are refused while too many of them do.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use crate::error::Strictness;
+use crate::execute::{self, Config};
+use crate::input::InputSource;
+use crate::registry;
+use crate::report::{self, format_duration, json_string};
+use crate::solution::{Part, Runner};
+
+/// Largest request head, i.e. request line and headers, in bytes.
+const MAX_HEAD: usize = 8 * 1024;
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What a request may cost.
+#[derive(Debug, Clone)]
+pub struct Limits {
+    /// Largest puzzle input accepted, in bytes.
+    pub max_input: usize,
+    /// How long solving one request may take.
+    pub time_budget: Duration,
+    /// Solves still running past their budget before new ones are refused.
+    pub max_overdue: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_input: 1024 * 1024,
+            time_budget: Duration::from_secs(10),
+            max_overdue: 2,
+        }
+    }
+}
+
+/// A bound server, see the [module documentation](self).
+pub struct Server {
+    listener: TcpListener,
+    config: Config,
+    limits: Limits,
+    /// Solves that have not finished yet, including ones past their budget.
+    running: Arc<AtomicUsize>,
+}
+
+impl Server {
+    /// Listens on `addr`. The year, strictness and options of `config` are the
+    /// defaults of every request.
+    pub fn bind<A: ToSocketAddrs>(addr: A, config: Config, limits: Limits) -> io::Result<Self> {
+        Ok(Server {
+            listener: TcpListener::bind(addr)?,
+            config,
+            limits,
+            running: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serves requests until accepting a connection fails.
+    pub fn run(&self) -> io::Result<()> {
+        loop {
+            self.handle_next()?;
+        }
+    }
+
+    /// Accepts one connection and answers its request. Errors talking to the
+    /// client are reported on stderr, only failing to accept is returned.
+    pub fn handle_next(&self) -> io::Result<()> {
+        let (stream, peer) = self.listener.accept()?;
+        if let Err(err) = self.handle(stream) {
+            eprintln!("{}: {}", peer, err);
+        }
+        Ok(())
+    }
+
+    fn handle(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let response = match read_request(&mut stream, self.limits.max_input) {
+            Ok(request) => self.route(&request),
+            Err(response) => response,
+        };
+        response.write_to(&mut stream)
+    }
+
+    fn route(&self, request: &Request) -> Response {
+        let segments: Vec<_> = request.path.split('/').filter(|s| !s.is_empty()).collect();
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["days"]) => Response::ok(self.days()),
+            ("POST", ["solve", day]) => self.solve(request, day, None),
+            ("POST", ["solve", day, part]) => self.solve(request, day, Some(part)),
+            (_, ["days"] | ["solve", ..]) => Response::error(405, "Method not allowed"),
+            _ => Response::error(404, &format!("No such endpoint {}", request.path)),
+        }
+    }
+
+    fn days(&self) -> String {
+        let days: Vec<_> = registry::days(self.config.year)
+            .map(|runner| {
+                format!(
+                    "{{\"day\":{},\"title\":{}}}",
+                    runner.day(),
+                    json_string(runner.title())
+                )
+            })
+            .collect();
+        format!(
+            "{{\"year\":{},\"days\":[{}]}}",
+            self.config.year,
+            days.join(",")
+        )
+    }
+
+    fn solve(&self, request: &Request, day: &str, part: Option<&str>) -> Response {
+        let year = self.config.year;
+        let Some(runner) = day.parse().ok().and_then(|day| registry::get(year, day)) else {
+            return Response::error(
+                404,
+                &format!(
+                    "Day {} of {} not implemented. Available days: {}",
+                    day,
+                    year,
+                    registry::available_days(year)
+                ),
+            );
+        };
+        let parts = match part {
+            Some(part) => match Part::try_from(part) {
+                Ok(part) => vec![part],
+                Err(_) => return Response::error(404, &format!("Unknown part {}", part)),
+            },
+            None => Part::ALL.to_vec(),
+        };
+        let text = match String::from_utf8(request.body.clone()) {
+            Ok(text) => text,
+            Err(_) => return Response::error(400, "The puzzle input is not UTF-8"),
+        };
+
+        // The body stands in for stdin, which leaves the input path out of the
+        // results.
+        let mut config = Config {
+            input: InputSource::Stdin,
+            ..self.config.clone()
+        };
+        for (name, value) in &request.query {
+            match name.as_str() {
+                "strict" if matches!(value.as_str(), "" | "true") => {
+                    config.strictness = Strictness::Strict
+                }
+                "strict" if value == "false" => config.strictness = Strictness::Lenient,
+                _ => config.options.push((name.clone(), value.clone())),
+            }
+        }
+        for &part in &parts {
+            if let Err(err) = config.options(runner, part) {
+                return Response::error(400, &err.to_string());
+            }
+        }
+
+        if self.running.load(Ordering::SeqCst) >= self.limits.max_overdue {
+            return Response::error(503, "Too many solves still running, try again later");
+        }
+        match self.run_with_budget(runner, parts, config, text) {
+            Some(response) => response,
+            None => Response::error(
+                504,
+                &format!(
+                    "Day {:02} did not finish within {}",
+                    runner.day(),
+                    format_duration(self.limits.time_budget)
+                ),
+            ),
+        }
+    }
+
+    /// Solves on another thread, returning the response or `None` if the time
+    /// budget runs out first.
+    fn run_with_budget(
+        &self,
+        runner: &'static dyn Runner,
+        parts: Vec<Part>,
+        config: Config,
+        text: String,
+    ) -> Option<Response> {
+        /// Counts the solve as running until its thread ends, panicking or not.
+        struct Running(Arc<AtomicUsize>);
+
+        impl Drop for Running {
+            fn drop(&mut self) {
+                self.0.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        self.running.fetch_add(1, Ordering::SeqCst);
+        let running = Running(Arc::clone(&self.running));
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let problems = match execute::catch_panic(|| runner.check(&text)) {
+                Ok(problems) => problems,
+                Err(message) => {
+                    drop(running);
+                    let _ = sender.send(Response::error(
+                        500,
+                        &format!(
+                            "Day {:02} panicked checking the input: {}",
+                            runner.day(),
+                            message
+                        ),
+                    ));
+                    return;
+                }
+            };
+            let results = execute::run_text(runner, &parts, &config, &text);
+
+            let results: Vec<_> = results
+                .iter()
+                .map(|result| report::to_json(result, &config))
+                .collect();
+            let problems: Vec<_> = problems
+                .iter()
+                .map(|problem| json_string(&problem.to_string()))
+                .collect();
+            drop(running);
+            let _ = sender.send(Response::ok(format!(
+                "{{\"day\":{},\"title\":{},\"results\":[{}],\"problems\":[{}]}}",
+                runner.day(),
+                json_string(runner.title()),
+                results.join(","),
+                problems.join(",")
+            )));
+        });
+        receiver.recv_timeout(self.limits.time_budget).ok()
+    }
+}
+
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    /// The decoded `name=value` pairs of the query string, in order.
+    query: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Response {
+            status,
+            body: format!("{{\"error\":{}}}", json_string(message)),
+        }
+    }
+
+    fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            411 => "Length Required",
+            413 => "Payload Too Large",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            _ => "Internal Server Error",
+        };
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{}",
+            self.status,
+            reason,
+            self.body.len(),
+            self.body
+        )?;
+        writer.flush()
+    }
+}
+
+/// Reads a request whose body is at most `max_input` bytes, or the response
+/// rejecting it.
+fn read_request<R: Read>(stream: R, max_input: usize) -> Result<Request, Response> {
+    let mut reader = BufReader::new(stream);
+    let mut head = Vec::new();
+    loop {
+        let line = read_head_line(&mut reader, MAX_HEAD - head.len())?;
+        if line.is_empty() || line == "\r\n" || line == "\n" {
+            break;
+        }
+        head.push(line);
+    }
+
+    let request_line = head
+        .first()
+        .ok_or_else(|| Response::error(400, "Empty request"))?;
+    let mut words = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (words.next(), words.next(), words.next())
+    else {
+        return Err(Response::error(400, "Malformed request line"));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut length = None;
+    for header in &head[1..] {
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| Response::error(400, "Malformed header"))?;
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            let value = value.trim();
+            length =
+                Some(value.parse::<usize>().map_err(|_| {
+                    Response::error(400, &format!("Invalid Content-Length {}", value))
+                })?);
+        } else if name.trim().eq_ignore_ascii_case("transfer-encoding") {
+            return Err(Response::error(411, "Chunked requests are not supported"));
+        }
+    }
+
+    let body = match (method, length) {
+        (_, Some(length)) if length > max_input => {
+            return Err(Response::error(
+                413,
+                &format!(
+                    "Input of {} exceeds the limit of {}",
+                    report::format_bytes(length as u64),
+                    report::format_bytes(max_input as u64)
+                ),
+            ))
+        }
+        (_, Some(length)) => {
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).map_err(read_error)?;
+            body
+        }
+        ("POST", None) => return Err(Response::error(411, "Missing Content-Length")),
+        (_, None) => Vec::new(),
+    };
+
+    Ok(Request {
+        method: method.to_owned(),
+        path: percent_decode(path),
+        query: query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (percent_decode(name), percent_decode(value))
+            })
+            .collect(),
+        body,
+    })
+}
+
+/// Reads a line of the request head, of at most `limit` bytes.
+fn read_head_line<R: BufRead>(reader: &mut R, limit: usize) -> Result<String, Response> {
+    let mut line = Vec::new();
+    reader
+        .take(limit as u64 + 1)
+        .read_until(b'\n', &mut line)
+        .map_err(read_error)?;
+    if line.len() > limit {
+        return Err(Response::error(431, "Request head too large"));
+    }
+    String::from_utf8(line).map_err(|_| Response::error(400, "Request head is not UTF-8"))
+}
+
+fn read_error(err: io::Error) -> Response {
+    match err.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+            Response::error(408, "Timed out reading the request")
+        }
+        io::ErrorKind::UnexpectedEof => Response::error(400, "Request ended early"),
+        _ => Response::error(400, &err.to_string()),
+    }
+}
+
+/// Decodes `%XX` escapes and `+` as space; invalid escapes are kept as they are.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+            (b'+', _) => decoded.push(b' '),
+            (byte, _) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::input::{DEFAULT_INPUT_DIR, DEFAULT_YEAR};
+
+    /// Starts a server on a free local port answering `requests` requests, and
+    /// returns its address.
+    fn start(limits: Limits, requests: usize) -> SocketAddr {
+        let server = Server::bind("127.0.0.1:0", Config::default(), limits).unwrap();
+        let addr = server.local_addr().unwrap();
+        thread::spawn(move || {
+            for _ in 0..requests {
+                server.handle_next().unwrap();
+            }
+        });
+        addr
+    }
+
+    /// Sends a raw request and returns the status and body of the response.
+    fn send(addr: SocketAddr, request: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, body.to_owned())
+    }
+
+    fn post(addr: SocketAddr, target: &str, body: &str) -> (u16, String) {
+        send(
+            addr,
+            &format!(
+                "POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+                target,
+                body.len(),
+                body
+            ),
+        )
+    }
+
+    fn example(day: u8) -> String {
+        InputSource::Named("example".into())
+            .read(Path::new(DEFAULT_INPUT_DIR), DEFAULT_YEAR, day)
+            .unwrap()
+    }
+
+    #[test]
+    fn solve_should_answer_with_results_and_problems() {
+        let addr = start(Limits::default(), 3);
+
+        let (status, body) = post(addr, "/solve/4", &example(4));
+        assert_eq!(200, status, "{}", body);
+        assert!(body.starts_with("{\"day\":4,\"title\":\"Camp Cleanup\",\"results\":["));
+        assert!(body.contains("\"part\":1,\"answer\":2,"), "{}", body);
+        assert!(body.contains("\"part\":2,\"answer\":4,"), "{}", body);
+        assert!(body.ends_with("\"problems\":[]}"), "{}", body);
+
+        let (status, body) = post(addr, "/solve/9/2?knots=2", &example(9));
+        assert_eq!(200, status, "{}", body);
+        assert!(body.contains("\"part\":2,\"answer\":13,"), "{}", body);
+        assert!(!body.contains("\"part\":1"), "{}", body);
+
+        let (status, body) = post(addr, "/solve/4?strict", "2-4,6-8\nnonsense\n");
+        assert_eq!(200, status, "{}", body);
+        assert!(body.contains("\"status\":\"error\""), "{}", body);
+        assert!(!body.contains("\"problems\":[]"), "{}", body);
+    }
+
+    #[test]
+    fn bad_requests_should_be_rejected() {
+        let limits = Limits {
+            max_input: 16,
+            ..Limits::default()
+        };
+        let addr = start(limits, 7);
+
+        assert_eq!(413, post(addr, "/solve/1", &"1\n".repeat(9)).0);
+        assert_eq!(404, post(addr, "/solve/42", "").0);
+        assert_eq!(404, post(addr, "/nothing", "").0);
+        assert_eq!(405, send(addr, "GET /solve/1 HTTP/1.1\r\n\r\n").0);
+        assert_eq!(411, send(addr, "POST /solve/1 HTTP/1.1\r\n\r\n").0);
+        let (status, body) = post(addr, "/solve/9?knots=x", "R 4\n");
+        assert_eq!(400, status);
+        assert!(body.contains("knots"), "{}", body);
+        let (status, body) = post(addr, "/solve/9?knots=10000000000", "R 4\n");
+        assert_eq!(400, status);
+        assert!(body.contains("from 1 to 1000"), "{}", body);
+    }
+
+    #[test]
+    fn slow_solves_should_exceed_the_time_budget() {
+        let limits = Limits {
+            time_budget: Duration::ZERO,
+            ..Limits::default()
+        };
+        let addr = start(limits, 1);
+
+        let (status, body) = post(addr, "/solve/11/2", &example(11));
+        assert_eq!(504, status, "{}", body);
+        assert!(body.contains("did not finish"), "{}", body);
+    }
+
+    #[test]
+    fn days_should_list_the_days_of_the_year() {
+        let addr = start(Limits::default(), 1);
+
+        let (status, body) = send(addr, "GET /days HTTP/1.1\r\n\r\n");
+        assert_eq!(200, status);
+        assert!(
+            body.starts_with("{\"year\":2022,\"days\":[{\"day\":1,"),
+            "{}",
+            body
+        );
+    }
+
+    #[test]
+    fn percent_decode_should_decode_escapes_and_plus() {
+        assert_eq!("a b/c", percent_decode("a+b%2Fc"));
+        assert_eq!("100%", percent_decode("100%"));
+        assert_eq!("%zz", percent_decode("%zz"));
+    }
+}
@@ -0,0 +1,99 @@
+//! Puzzles that advance one step at a time, so that they can be paused,
+//! inspected and rewound.
+
+/// A simulation advancing one step at a time until it is done.
+pub trait Simulation {
+    /// A copy of the state, to return to with [`Simulation::restore`].
+    type Snapshot: Clone;
+
+    /// Advances by one step. Does nothing once the simulation is done.
+    fn step(&mut self);
+
+    fn is_done(&self) -> bool;
+
+    fn snapshot(&self) -> Self::Snapshot;
+
+    fn restore(&mut self, snapshot: &Self::Snapshot);
+
+    /// The state as text, for people to inspect.
+    fn dump(&self) -> String;
+}
+
+/// Runs at most `steps` steps and returns how many were taken before the
+/// simulation was done.
+pub fn run<S: Simulation>(simulation: &mut S, steps: usize) -> usize {
+    let mut taken = 0;
+    while taken < steps && !simulation.is_done() {
+        simulation.step();
+        taken += 1;
+    }
+    taken
+}
+
+/// Runs until the simulation is done and returns the number of steps taken.
+pub fn run_to_end<S: Simulation>(simulation: &mut S) -> usize {
+    run(simulation, usize::MAX)
+}
+
+/// Runs until the simulation is done, recording the state before the first
+/// step and after every step. Restoring the snapshot at index `n` replays the
+/// run up to step `n`.
+pub fn record<S: Simulation>(simulation: &mut S) -> Vec<S::Snapshot> {
+    let mut trace = vec![simulation.snapshot()];
+    while !simulation.is_done() {
+        simulation.step();
+        trace.push(simulation.snapshot());
+    }
+    trace
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day05::{Crane, Day05, Model};
+    use crate::day09::{Day09, RopeBridge};
+    use crate::day10::{CathodRayTube, Day10};
+    use crate::day11::{Day11, Relief, Rounds};
+    use crate::testing::example;
+
+    /// Checks that stepping, recording and replaying agree, and returns the
+    /// number of steps.
+    fn check_replay<S: Simulation>(mut simulation: S) -> usize {
+        let start = simulation.snapshot();
+        assert_eq!(2, run(&mut simulation, 2));
+        let after_two = simulation.dump();
+        let steps = 2 + run_to_end(&mut simulation);
+        let end = simulation.dump();
+        assert_eq!(0, run(&mut simulation, 1));
+
+        simulation.restore(&start);
+        let trace = record(&mut simulation);
+        assert_eq!(steps + 1, trace.len());
+        assert_eq!(end, simulation.dump());
+        simulation.restore(&trace[2]);
+        assert_eq!(after_two, simulation.dump());
+        assert!(!simulation.is_done());
+        steps
+    }
+
+    #[test]
+    fn simulations_should_replay_their_steps() {
+        let procedure = example::<Day05>("example");
+        assert_eq!(
+            4,
+            check_replay(Crane::new(&procedure, Model::CrateMover9000))
+        );
+        let motions = example::<Day09>("example");
+        assert_eq!(24, check_replay(RopeBridge::new(&motions, 2)));
+        let program = example::<Day10>("example");
+        assert_eq!(240, check_replay(CathodRayTube::new(&program)));
+        assert_eq!(
+            20,
+            check_replay(Rounds::new(
+                example::<Day11>("example"),
+                Relief::DivideByThree,
+                20
+            ))
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use std::fmt;
+
+use crate::error::{Error, ParseError, Result, Strictness};
+use crate::generate::Rng;
+use crate::options::{OptionSpec, Options};
+use crate::visualize::Frames;
+
+/// One of the two parts every puzzle consists of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl TryFrom<&str> for Part {
+    type Error = ();
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        match s {
+            "1" | "part1" => Ok(Part::One),
+            "2" | "part2" => Ok(Part::Two),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+    /// The input did not contain what the puzzle asked for.
+    None,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::None => write!(f, "none"),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Self {
+        Answer::Number(n.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Number(n)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Number(n as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_owned())
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(answer: Option<T>) -> Self {
+        answer.map_or(Answer::None, Into::into)
+    }
+}
+
+/// A puzzle solution: the input is parsed once and both parts are computed from it.
+pub trait Solution {
+    const DAY: u8;
+    const TITLE: &'static str;
+
+    type Input;
+
+    /// The options understood by [`Solution::part1`] and [`Solution::part2`].
+    const OPTIONS: &'static [OptionSpec] = &[];
+
+    /// The options understood by [`Solution::generate`], like the size of the
+    /// input. Both defaults of each option should be the same.
+    const GENERATOR: &'static [OptionSpec] = &[];
+
+    /// Parses the puzzle input, skipping or reporting malformed lines depending on
+    /// `strictness`.
+    fn parse_with(input: &str, strictness: Strictness) -> Result<Self::Input>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Self::parse_with(input, Strictness::Lenient)
+    }
+
+    /// Checks the shape of the puzzle input without solving it and returns every
+    /// problem found. Days that do not override it report the first error of
+    /// parsing strictly.
+    fn check(input: &str) -> Vec<ParseError> {
+        match Self::parse_with(input, Strictness::Strict) {
+            Err(Error::Parse(err)) => vec![err],
+            _ => Vec::new(),
+        }
+    }
+
+    /// A random valid puzzle input, or `None` if the day has no generator.
+    fn generate(_rng: &mut Rng, _options: &Options) -> Option<String> {
+        None
+    }
+
+    fn part1(input: &Self::Input, options: &Options) -> Answer;
+
+    fn part2(input: &Self::Input, options: &Options) -> Answer;
+
+    /// The frames showing how `part` is solved, or `None` if the day has no
+    /// visualization.
+    fn visualize<'a>(
+        _input: &'a Self::Input,
+        _part: Part,
+        _options: &Options,
+    ) -> Option<Frames<'a>> {
+        None
+    }
+
+    /// Human readable sentence presenting the answer of a part.
+    fn describe(part: Part, _options: &Options, answer: &Answer) -> String {
+        format!("Part {}: {}", part, answer)
+    }
+}
+
+/// Object safe view of a [`Solution`], so that days can be kept in a registry.
+pub trait Runner: Sync {
+    fn day(&self) -> u8;
+
+    fn title(&self) -> &'static str;
+
+    fn options(&self) -> &'static [OptionSpec];
+
+    fn parse(&self, input: &str, strictness: Strictness) -> Result<Box<dyn Parsed>>;
+
+    fn check(&self, input: &str) -> Vec<ParseError>;
+
+    fn generator_options(&self) -> &'static [OptionSpec];
+
+    fn generate(&self, rng: &mut Rng, options: &Options) -> Option<String>;
+
+    fn describe(&self, part: Part, options: &Options, answer: &Answer) -> String;
+}
+
+/// A parsed puzzle input, ready to be solved.
+pub trait Parsed {
+    fn solve(&self, part: Part, options: &Options) -> Answer;
+
+    fn visualize(&self, part: Part, options: &Options) -> Option<Frames<'_>>;
+}
+
+struct ParsedInput<S: Solution> {
+    input: S::Input,
+}
+
+impl<S: Solution> Parsed for ParsedInput<S> {
+    fn solve(&self, part: Part, options: &Options) -> Answer {
+        match part {
+            Part::One => S::part1(&self.input, options),
+            Part::Two => S::part2(&self.input, options),
+        }
+    }
+
+    fn visualize(&self, part: Part, options: &Options) -> Option<Frames<'_>> {
+        S::visualize(&self.input, part, options)
+    }
+}
+
+impl<S> Runner for S
+where
+    S: Solution + Sync + 'static,
+    S::Input: 'static,
+{
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn options(&self) -> &'static [OptionSpec] {
+        S::OPTIONS
+    }
+
+    fn parse(&self, input: &str, strictness: Strictness) -> Result<Box<dyn Parsed>> {
+        let input = S::parse_with(input, strictness)?;
+        Ok(Box::new(ParsedInput::<S> { input }))
+    }
+
+    fn check(&self, input: &str) -> Vec<ParseError> {
+        S::check(input)
+    }
+
+    fn generator_options(&self) -> &'static [OptionSpec] {
+        S::GENERATOR
+    }
+
+    fn generate(&self, rng: &mut Rng, options: &Options) -> Option<String> {
+        S::generate(rng, options)
+    }
+
+    fn describe(&self, part: Part, options: &Options, answer: &Answer) -> String {
+        S::describe(part, options, answer)
+    }
+}
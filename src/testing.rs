@@ -0,0 +1,83 @@
+//! Helpers shared by the tests of the days.
+
+use std::path::Path;
+
+use crate::error::Strictness;
+use crate::input::{InputSource, DEFAULT_YEAR};
+use crate::options::Options;
+use crate::solution::{Answer, Part, Solution};
+
+/// Parses the input set `set` of `S`, e.g. `input/2022/day09/example2.txt`,
+/// which has to parse strictly.
+pub fn example<S: Solution>(set: &str) -> S::Input {
+    example_of_year::<S>(DEFAULT_YEAR, set)
+}
+
+/// Like [`example`], for a day of `year`.
+pub fn example_of_year<S: Solution>(year: u16, set: &str) -> S::Input {
+    let input_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("input");
+    let text = InputSource::Named(set.into())
+        .read(&input_dir, year, S::DAY)
+        .unwrap();
+    S::parse_with(&text, Strictness::Strict).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Solves `part` of the input set `set` of `S` of `year` and compares the answer
+/// with `expected`.
+pub fn check_example<S: Solution>(year: u16, set: &str, part: Part, expected: impl Into<Answer>) {
+    let input = example_of_year::<S>(year, set);
+
+    let options = Options::defaults(S::OPTIONS, part);
+    let answer = match part {
+        Part::One => S::part1(&input, &options),
+        Part::Two => S::part2(&input, &options),
+    };
+
+    assert_eq!(
+        expected.into(),
+        answer,
+        "day {:02} {} part {}",
+        S::DAY,
+        set,
+        part
+    );
+}
+
+/// Generates one test per input set and part, comparing the answer of the day
+/// with the expected one. Each set becomes a module holding `part1` and/or
+/// `part2` tests. The inputs are those of 2022 unless a year follows the day:
+///
+/// ```ignore
+/// example_tests! {
+///     Day09;
+///     example: part1 = 13, part2 = 1;
+///     example2: part2 = 36;
+/// }
+/// ```
+macro_rules! example_tests {
+    ($day:ty; $($rest:tt)+) => {
+        $crate::testing::example_tests! { $day, $crate::input::DEFAULT_YEAR; $($rest)+ }
+    };
+    ($day:ty, $year:expr; $($set:ident: $($part:ident = $expected:expr),+;)+) => {
+        $(
+            mod $set {
+                #[allow(unused_imports)]
+                use super::*;
+
+                $(
+                    #[test]
+                    fn $part() {
+                        $crate::testing::check_example::<$day>(
+                            $year,
+                            stringify!($set),
+                            $crate::solution::Part::try_from(stringify!($part)).unwrap(),
+                            $expected,
+                        );
+                    }
+                )+
+            }
+        )+
+    };
+}
+
+pub(crate) use example_tests;
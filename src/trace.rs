@@ -0,0 +1,222 @@
+//! Level-filtered events describing what the days do, for finding out why an
+//! answer is wrong.
+//!
+//! Days record events with [`event!`], which costs an atomic load while its
+//! level is disabled: the fields are not even evaluated. Enabled events are
+//! written as one line each, like
+//!
+//! ```text
+//! [TRACE day11] throw monkey=0 worry=500 to=3
+//! ```
+//!
+//! to stderr or to the file given to [`init`].
+
+use std::fmt::{self, Display};
+use std::fs::File;
+use std::io::{self, LineWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+
+/// How detailed an event is. Enabling a level enables the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// A few events per part, like intermediate results.
+    Info = 1,
+    /// An event per line of input or step of the solution.
+    Debug = 2,
+    /// Everything, possibly millions of events.
+    Trace = 3,
+}
+
+impl Level {
+    /// The level of `-v`, `-vv` and `-vvv`, `None` for no flag.
+    pub fn from_verbosity(verbosity: usize) -> Option<Level> {
+        match verbosity {
+            0 => None,
+            1 => Some(Level::Info),
+            2 => Some(Level::Debug),
+            _ => Some(Level::Trace),
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Info => write!(f, "INFO"),
+            Level::Debug => write!(f, "DEBUG"),
+            Level::Trace => write!(f, "TRACE"),
+        }
+    }
+}
+
+/// The most detailed level enabled, 0 if tracing is off.
+static ENABLED: AtomicU8 = AtomicU8::new(0);
+
+/// Where events go, stderr if `None`.
+static SINK: Mutex<Option<LineWriter<File>>> = Mutex::new(None);
+
+/// Enables the events up to `level`, or none, and writes them to `file`
+/// instead of stderr if given.
+pub fn init(level: Option<Level>, file: Option<&Path>) -> io::Result<()> {
+    let sink = file.map(File::create).transpose()?.map(LineWriter::new);
+    *SINK.lock().unwrap_or_else(|err| err.into_inner()) = sink;
+    ENABLED.store(level.map_or(0, |level| level as u8), Ordering::Relaxed);
+    Ok(())
+}
+
+/// Whether events at `level` are recorded.
+#[inline]
+pub fn enabled(level: Level) -> bool {
+    #[cfg(test)]
+    if tests::capturing() {
+        return true;
+    }
+    level as u8 <= ENABLED.load(Ordering::Relaxed)
+}
+
+/// Writes an event, see [`event!`].
+pub fn write_event(level: Level, module: &str, name: &str, fields: &[(&str, &dyn Display)]) {
+    let line = format_event(level, module, name, fields);
+    #[cfg(test)]
+    if tests::record(&line) {
+        return;
+    }
+
+    let mut sink = SINK.lock().unwrap_or_else(|err| err.into_inner());
+    match sink.as_mut() {
+        // Tracing must not make a day fail, a full disk only loses events.
+        Some(file) => _ = writeln!(file, "{}", line),
+        None => eprintln!("{}", line),
+    }
+}
+
+/// `[LEVEL target] name field=value...`, the target being the module without
+/// the crate name. Values with spaces or quotes are quoted.
+fn format_event(level: Level, module: &str, name: &str, fields: &[(&str, &dyn Display)]) -> String {
+    let target = module.rsplit("::").next().unwrap_or(module);
+    let mut line = format!("[{} {}] {}", level, target, name);
+    for (field, value) in fields {
+        let value = value.to_string();
+        if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"') {
+            line += &format!(" {}={:?}", field, value);
+        } else {
+            line += &format!(" {}={}", field, value);
+        }
+    }
+    line
+}
+
+/// Records an event named `$name` with `field = value` pairs if `$level` is
+/// enabled, e.g. `event!(Level::Debug, "cd", dir = name)`.
+macro_rules! event {
+    ($level:expr, $name:expr $(, $field:ident = $value:expr)* $(,)?) => {
+        if $crate::trace::enabled($level) {
+            $crate::trace::write_event(
+                $level,
+                module_path!(),
+                $name,
+                &[$((stringify!($field), &$value as &dyn ::std::fmt::Display)),*],
+            );
+        }
+    };
+}
+
+pub(crate) use event;
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::day07::Day07;
+    use crate::day10::Day10;
+    use crate::day11::Day11;
+    use crate::input::DEFAULT_YEAR;
+    use crate::solution::Solution;
+    use crate::testing::check_example;
+    use crate::Part;
+
+    thread_local! {
+        static CAPTURED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+    }
+
+    pub fn capturing() -> bool {
+        CAPTURED.with(|captured| captured.borrow().is_some())
+    }
+
+    /// Keeps `line` and returns `true` if this thread is capturing.
+    pub fn record(line: &str) -> bool {
+        CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+            Some(events) => {
+                events.push(line.to_string());
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Runs `f` and returns the events of every level it recorded on this
+    /// thread, which are not written anywhere else. Other threads are not
+    /// affected.
+    fn capture<T, F: FnOnce() -> T>(f: F) -> (T, Vec<String>) {
+        /// Ends the capture, also when `f` panics.
+        struct Restore(Option<Vec<String>>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CAPTURED.with(|captured| *captured.borrow_mut() = self.0.take());
+            }
+        }
+
+        let previous = CAPTURED.with(|captured| captured.borrow_mut().replace(Vec::new()));
+        let restore = Restore(previous);
+        let result = f();
+        let events = CAPTURED.with(|captured| captured.borrow_mut().take().unwrap_or_default());
+        drop(restore);
+        (result, events)
+    }
+
+    #[test]
+    fn format_event_should_quote_values_with_spaces() {
+        let line = format_event(
+            Level::Debug,
+            "advent_of_code_rs::day07",
+            "file",
+            &[("name", &"a b"), ("size", &14848514), ("empty", &"")],
+        );
+
+        assert_eq!(
+            "[DEBUG day07] file name=\"a b\" size=14848514 empty=\"\"",
+            line
+        );
+    }
+
+    #[test]
+    fn capture_should_enable_only_its_own_thread() {
+        let ((), events) = capture(|| {
+            assert!(enabled(Level::Trace));
+            let other = std::thread::spawn(|| enabled(Level::Trace));
+            assert!(!other.join().unwrap());
+        });
+
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn days_should_record_their_steps() {
+        let ((), events) = capture(|| {
+            Day10::parse("noop\naddx 3\n").unwrap();
+            Day07::parse("$ cd /\n$ ls\ndir a\n").unwrap();
+            check_example::<Day11>(DEFAULT_YEAR, "example", Part::One, 10605);
+        });
+
+        assert!(events.contains(&"[TRACE day10] instruction line=1 instruction=noop".into()));
+        assert!(events.contains(&"[TRACE day10] instruction line=2 instruction=\"addx 3\"".into()));
+        assert!(events.contains(&"[DEBUG day07] cd dir=/".into()));
+        assert!(events.contains(&"[DEBUG day07] ls path=/".into()));
+        assert!(events.contains(&"[TRACE day11] throw monkey=0 worry=500 to=3".into()));
+        assert!(events.contains(&"[INFO day11] inspections monkey=3 count=105".into()));
+    }
+}
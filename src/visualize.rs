@@ -0,0 +1,359 @@
+//! Pictures of what the days do, one frame per step: written as PPM or SVG
+//! images, or played back in the terminal with ANSI colours.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use crate::grid::{Grid, Point};
+use crate::simulation::Simulation;
+
+/// The frames of a day, produced one at a time as they are needed.
+pub type Frames<'a> = Box<dyn Iterator<Item = Frame> + 'a>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BACKGROUND: Rgb = Rgb(16, 16, 24);
+
+    /// Whether text on this colour should be dark rather than light.
+    fn is_light(self) -> bool {
+        let Rgb(r, g, b) = self;
+        299 * r as u32 + 587 * g as u32 + 114 * b as u32 > 128_000
+    }
+
+    /// A colour telling `index` apart from its neighbours, e.g. one per letter.
+    pub fn distinct(index: usize) -> Rgb {
+        const PALETTE: [Rgb; 8] = [
+            Rgb(230, 25, 75),
+            Rgb(60, 180, 75),
+            Rgb(255, 225, 25),
+            Rgb(67, 99, 216),
+            Rgb(245, 130, 49),
+            Rgb(145, 30, 180),
+            Rgb(66, 212, 244),
+            Rgb(240, 50, 230),
+        ];
+        PALETTE[index % PALETTE.len()]
+    }
+
+    /// Between `self` at 0 and `other` at 1.
+    pub fn mix(self, other: Rgb, fraction: f64) -> Rgb {
+        let channel = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * fraction) as u8;
+        Rgb(
+            channel(self.0, other.0),
+            channel(self.1, other.1),
+            channel(self.2, other.2),
+        )
+    }
+}
+
+/// One cell of a frame: its colour, and the character shown on it in the
+/// terminal and in SVG images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub color: Rgb,
+    pub glyph: char,
+}
+
+impl Cell {
+    pub const EMPTY: Cell = Cell::new(Rgb::BACKGROUND, ' ');
+
+    pub const fn new(color: Rgb, glyph: char) -> Self {
+        Cell { color, glyph }
+    }
+}
+
+/// A picture of the state of a day, with a line describing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub cells: Grid<Cell>,
+    pub caption: String,
+}
+
+impl Frame {
+    /// An empty frame.
+    pub fn new(width: usize, height: usize) -> Self {
+        Frame {
+            cells: Grid::new(width, height, Cell::EMPTY),
+            caption: String::new(),
+        }
+    }
+
+    /// Sets the cell at `point`, if it is inside the frame.
+    pub fn set(&mut self, point: Point, cell: Cell) {
+        if let Some(target) = self.cells.get_mut(point) {
+            *target = cell;
+        }
+    }
+}
+
+/// The frames of a simulation: one before the first step and one after each
+/// step, drawn by `draw`.
+pub fn frames_of<'a, S, F>(mut simulation: S, mut draw: F) -> Frames<'a>
+where
+    S: Simulation + 'a,
+    F: FnMut(&S) -> Frame + 'a,
+{
+    let mut started = false;
+    Box::new(std::iter::from_fn(move || {
+        if started {
+            if simulation.is_done() {
+                return None;
+            }
+            simulation.step();
+        }
+        started = true;
+        Some(draw(&simulation))
+    }))
+}
+
+/// How frames are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// One binary PPM image per frame.
+    Ppm,
+    /// One SVG image per frame.
+    Svg,
+    /// An animation in the terminal.
+    Ansi,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ppm" => Ok(Format::Ppm),
+            "svg" => Ok(Format::Svg),
+            "ansi" => Ok(Format::Ansi),
+            _ => Err(format!("Unknown format {}, expected ppm, svg or ansi", s)),
+        }
+    }
+}
+
+/// Encodes a frame as a binary PPM image with `scale` pixels per cell side.
+pub fn ppm(frame: &Frame, scale: usize) -> Vec<u8> {
+    let (width, height) = (frame.cells.width() * scale, frame.cells.height() * scale);
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    image.reserve(width * height * 3);
+    for y in 0..frame.cells.height() {
+        let row: Vec<u8> = frame
+            .cells
+            .row(y)
+            .flat_map(|cell| {
+                let Rgb(r, g, b) = cell.color;
+                [r, g, b].repeat(scale)
+            })
+            .collect();
+        for _ in 0..scale {
+            image.extend_from_slice(&row);
+        }
+    }
+    image
+}
+
+/// Encodes a frame as an SVG image with `scale` pixels per cell side. The
+/// caption becomes its title.
+pub fn svg(frame: &Frame, scale: usize) -> String {
+    let (width, height) = (frame.cells.width() * scale, frame.cells.height() * scale);
+    let mut image = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         font-family=\"monospace\" font-size=\"{}\" text-anchor=\"middle\">\n\
+         <title>{}</title>\n<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        width,
+        height,
+        scale * 4 / 5,
+        escape_xml(&frame.caption),
+        hex(Rgb::BACKGROUND)
+    );
+    for point in frame.cells.points() {
+        let cell = frame.cells[point];
+        let (x, y) = (point.x as usize * scale, point.y as usize * scale);
+        if cell.color != Rgb::BACKGROUND {
+            let _ = writeln!(
+                image,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                x,
+                y,
+                scale,
+                scale,
+                hex(cell.color)
+            );
+        }
+        if !cell.glyph.is_whitespace() {
+            let _ = writeln!(
+                image,
+                "<text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>",
+                x + scale / 2,
+                y + scale * 4 / 5,
+                hex(text_color(cell.color)),
+                escape_xml(&cell.glyph.to_string())
+            );
+        }
+    }
+    image.push_str("</svg>\n");
+    image
+}
+
+/// Draws a frame with ANSI colours, two columns per cell, followed by its
+/// caption.
+pub fn ansi(frame: &Frame) -> String {
+    let mut text = String::new();
+    for y in 0..frame.cells.height() {
+        let mut previous = None;
+        for cell in frame.cells.row(y) {
+            if previous != Some(cell.color) {
+                let (Rgb(r, g, b), Rgb(fr, fg, fb)) = (cell.color, text_color(cell.color));
+                let _ = write!(
+                    text,
+                    "\x1b[48;2;{};{};{};38;2;{};{};{}m",
+                    r, g, b, fr, fg, fb
+                );
+                previous = Some(cell.color);
+            }
+            text.push(cell.glyph);
+            text.push(' ');
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text.push_str(&frame.caption);
+    text.push('\n');
+    text
+}
+
+/// Plays the frames in the terminal at `fps` frames per second. Returns the
+/// number of frames shown.
+pub fn play<W: Write>(frames: Frames, fps: f64, mut out: W) -> io::Result<usize> {
+    const CLEAR_SCREEN: &str = "\x1b[2J";
+    const HOME: &str = "\x1b[H";
+    let delay = Duration::try_from_secs_f64(1.0 / fps).unwrap_or(Duration::MAX);
+
+    write!(out, "{}", CLEAR_SCREEN)?;
+    let mut shown = 0;
+    for frame in frames {
+        write!(out, "{}{}\x1b[J", HOME, ansi(&frame))?;
+        out.flush()?;
+        shown += 1;
+        thread::sleep(delay);
+    }
+    Ok(shown)
+}
+
+/// Writes each frame to `dir` as `frameNNNNN.ppm` or `.svg`, creating the
+/// directory. Returns the files written.
+pub fn write_frames(
+    frames: Frames,
+    dir: &Path,
+    format: Format,
+    scale: usize,
+) -> io::Result<Vec<PathBuf>> {
+    let extension = match format {
+        Format::Ppm => "ppm",
+        Format::Svg => "svg",
+        Format::Ansi => "ans",
+    };
+    fs::create_dir_all(dir)?;
+
+    let mut written = Vec::new();
+    for (index, frame) in frames.enumerate() {
+        let path = dir.join(format!("frame{:05}.{}", index, extension));
+        match format {
+            Format::Ppm => fs::write(&path, ppm(&frame, scale))?,
+            Format::Svg => fs::write(&path, svg(&frame, scale))?,
+            Format::Ansi => fs::write(&path, ansi(&frame))?,
+        }
+        written.push(path);
+    }
+    Ok(written)
+}
+
+fn text_color(background: Rgb) -> Rgb {
+    match background.is_light() {
+        true => Rgb(0, 0, 0),
+        false => Rgb(230, 230, 230),
+    }
+}
+
+fn hex(Rgb(r, g, b): Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day05::Day05;
+    use crate::day08::Day08;
+    use crate::day09::Day09;
+    use crate::day10::Day10;
+    use crate::options::Options;
+    use crate::solution::{Part, Solution};
+    use crate::testing::example;
+
+    fn frame() -> Frame {
+        let mut frame = Frame::new(2, 1);
+        frame.set(Point::new(1, 0), Cell::new(Rgb(255, 0, 0), '<'));
+        frame.set(Point::new(5, 5), Cell::new(Rgb(0, 255, 0), 'x'));
+        frame.caption = "a & b".into();
+        frame
+    }
+
+    #[test]
+    fn ppm_should_scale_the_cells() {
+        let image = ppm(&frame(), 2);
+
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(header, &image[..header.len()]);
+        let pixels = &image[header.len()..];
+        assert_eq!(4 * 2 * 3, pixels.len());
+        assert_eq!([16, 16, 24, 16, 16, 24, 255, 0, 0, 255, 0, 0], pixels[..12]);
+        assert_eq!(pixels[..12], pixels[12..]);
+    }
+
+    #[test]
+    fn svg_should_escape_text_and_skip_the_background() {
+        let image = svg(&frame(), 10);
+
+        assert!(image.contains("<title>a &amp; b</title>"));
+        assert_eq!(2, image.matches("<rect ").count());
+        assert!(
+            image.contains("<rect x=\"10\" y=\"0\" width=\"10\" height=\"10\" fill=\"#ff0000\"/>")
+        );
+        assert!(image.contains(">&lt;</text>"));
+    }
+
+    #[test]
+    fn ansi_should_change_colours_only_when_needed() {
+        let text = ansi(&frame());
+
+        assert_eq!(2, text.matches("\x1b[48;2;").count());
+        assert!(text.ends_with("< \x1b[0m\na & b\n"));
+    }
+
+    fn count_frames<S: Solution>(part: Part) -> usize {
+        let input = example::<S>("example");
+        S::visualize(&input, part, &Options::defaults(S::OPTIONS, part))
+            .unwrap()
+            .count()
+    }
+
+    #[test]
+    fn days_should_draw_a_frame_per_step() {
+        assert_eq!(5, count_frames::<Day05>(Part::Two));
+        assert_eq!(5, count_frames::<Day08>(Part::One));
+        assert_eq!(25, count_frames::<Day09>(Part::One));
+        assert_eq!(241, count_frames::<Day10>(Part::Two));
+    }
+}
@@ -0,0 +1,118 @@
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::error::{Error, Result};
+use crate::execute::{self, Config, Status};
+use crate::input::InputSource;
+use crate::report::format_duration;
+use crate::solution::{Part, Runner};
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// What is known about an input file, to notice when it changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Fingerprint {
+    Missing,
+    File {
+        modified: Option<SystemTime>,
+        len: u64,
+    },
+}
+
+impl Fingerprint {
+    fn of(path: &Path) -> Self {
+        match fs::metadata(path) {
+            Ok(metadata) => Fingerprint::File {
+                modified: metadata.modified().ok(),
+                len: metadata.len(),
+            },
+            Err(_) => Fingerprint::Missing,
+        }
+    }
+}
+
+/// Runs the given parts of a day on each of its inputs every time one of the
+/// input files changes, polling them every `interval`. `inputs` is asked for the
+/// inputs on every poll, so that new input sets are picked up. Only returns on
+/// errors.
+pub fn watch<F>(
+    runner: &dyn Runner,
+    parts: &[Part],
+    config: &Config,
+    interval: Duration,
+    inputs: F,
+) -> Result<()>
+where
+    F: Fn() -> Result<Vec<InputSource>>,
+{
+    let mut last = None;
+    loop {
+        let mut files = Vec::new();
+        for input in inputs()? {
+            let path = input
+                .path(&config.input_dir, config.year, runner.day())
+                .ok_or_else(|| Error::Usage("watch needs input files, not stdin".into()))?;
+            let fingerprint = Fingerprint::of(&path);
+            files.push((input, path, fingerprint));
+        }
+
+        if last.as_ref() != Some(&files) {
+            print!("{}", CLEAR_SCREEN);
+            println!(
+                "Day {:02}: {} - watching {}, press Ctrl-C to stop",
+                runner.day(),
+                runner.title(),
+                files
+                    .iter()
+                    .map(|(_, path, _)| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            for (input, path, fingerprint) in &files {
+                println!();
+                if files.len() > 1 {
+                    println!("{}:", input);
+                }
+                match fingerprint {
+                    Fingerprint::Missing => println!("Waiting for {} to exist", path.display()),
+                    Fingerprint::File { .. } => {
+                        let config = Config {
+                            input: input.clone(),
+                            ..config.clone()
+                        };
+                        run(runner, parts, &config)?
+                    }
+                }
+            }
+            last = Some(files);
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn run(runner: &dyn Runner, parts: &[Part], config: &Config) -> Result<()> {
+    let results = execute::with_silent_panics(|| execute::run_day(runner, parts, config));
+    if let Some(Status::Error(message)) = results.first().map(|result| &result.status) {
+        // Reading or parsing failed, which is the same for every part.
+        println!("error: {}", message);
+        return Ok(());
+    }
+
+    for result in results {
+        match (&result.status, &result.answer) {
+            (Status::Ok, Some(answer)) => {
+                let options = config.options(runner, result.part)?;
+                println!("{}", runner.describe(result.part, &options, answer));
+                println!(
+                    "    parsed in {}, solved in {}",
+                    format_duration(result.parse_time),
+                    format_duration(result.solve_time)
+                );
+            }
+            (status, _) => println!("Part {}: {}", result.part, status),
+        }
+    }
+    Ok(())
+}